@@ -0,0 +1,143 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+/// A point (or an offset) on a 2D grid.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Coord {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Coord {
+    pub const ORIGIN: Coord = Coord { x: 0, y: 0 };
+
+    pub fn new(x: i32, y: i32) -> Self {
+        Coord { x, y }
+    }
+
+    /// Manhattan distance between two coordinates.
+    pub fn manhattan(&self, other: &Coord) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// The four orthogonally adjacent coordinates.
+    pub fn neighbors(&self) -> [Coord; 4] {
+        [
+            *self + Coord::new(0, -1),
+            *self + Coord::new(1, 0),
+            *self + Coord::new(0, 1),
+            *self + Coord::new(-1, 0),
+        ]
+    }
+
+    /// All eight adjacent coordinates, including diagonals.
+    pub fn all_neighbors(&self) -> [Coord; 8] {
+        [
+            *self + Coord::new(0, -1),
+            *self + Coord::new(1, -1),
+            *self + Coord::new(1, 0),
+            *self + Coord::new(1, 1),
+            *self + Coord::new(0, 1),
+            *self + Coord::new(-1, 1),
+            *self + Coord::new(-1, 0),
+            *self + Coord::new(-1, -1),
+        ]
+    }
+}
+
+impl Add for Coord {
+    type Output = Coord;
+
+    fn add(self, rhs: Coord) -> Coord {
+        Coord::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Coord {
+    fn add_assign(&mut self, rhs: Coord) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl Sub for Coord {
+    type Output = Coord;
+
+    fn sub(self, rhs: Coord) -> Coord {
+        Coord::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Coord {
+    fn sub_assign(&mut self, rhs: Coord) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl Neg for Coord {
+    type Output = Coord;
+
+    fn neg(self) -> Coord {
+        Coord::new(-self.x, -self.y)
+    }
+}
+
+impl From<(i32, i32)> for Coord {
+    fn from((x, y): (i32, i32)) -> Self {
+        Coord::new(x, y)
+    }
+}
+
+impl From<Coord> for (i32, i32) {
+    fn from(c: Coord) -> Self {
+        (c.x, c.y)
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Coord::new(3, -4);
+        let b = Coord::new(-1, 2);
+
+        assert_eq!(a + b, Coord::new(2, -2));
+        assert_eq!(a - b, Coord::new(4, -6));
+        assert_eq!(-a, Coord::new(-3, 4));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+
+        assert_eq!(a.manhattan(&Coord::ORIGIN), 7);
+        assert_eq!(a.manhattan(&b), 10);
+        assert_eq!(<(i32, i32)>::from(a), (3, -4));
+        assert_eq!(Coord::from((3, -4)), a);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let c = Coord::new(5, 5);
+
+        assert!(c.neighbors().iter().all(|n| n.manhattan(&c) == 1));
+        assert_eq!(
+            c.all_neighbors()
+                .iter()
+                .filter(|n| n.manhattan(&c) == 2)
+                .count(),
+            4
+        );
+        assert!(c.neighbors().iter().all(|n| c.all_neighbors().contains(n)));
+        assert!(!c.all_neighbors().contains(&c));
+    }
+}
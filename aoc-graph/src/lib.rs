@@ -1,9 +1,15 @@
 use log::debug;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display};
 use std::hash::Hash;
 
+mod weighted;
+pub use weighted::WeightedGraph;
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Graph<V>
 where
     V: Hash + Debug + Eq + Clone,
@@ -11,6 +17,68 @@ where
     adjacency_list: HashMap<V, HashSet<V>>,
 }
 
+/// The graph is not a DAG, `vertex` is on a cycle.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CycleError<V> {
+    pub vertex: V,
+}
+
+impl<V: Debug> Display for CycleError<V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Graph contains a cycle through `{:?}`", self.vertex)
+    }
+}
+
+impl<V: Debug> std::error::Error for CycleError<V> {}
+
+/// DFS coloring used for cycle detection, unvisited vertices are simply absent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    /// The vertex is on the current DFS path.
+    InProgress,
+    /// The vertex and everything reachable from it was fully explored.
+    Done,
+}
+
+/// Depth-first preorder traversal, see `Graph::dfs`.
+struct Dfs<'a, V>
+where
+    V: Hash + Debug + Eq + Clone,
+{
+    graph: &'a Graph<V>,
+    stack: Vec<V>,
+    visited: HashSet<V>,
+}
+
+impl<'a, V> Iterator for Dfs<'a, V>
+where
+    V: Hash + Debug + Eq + Clone,
+{
+    type Item = V;
+
+    fn next(&mut self) -> Option<V> {
+        while let Some(v) = self.stack.pop() {
+            if !self.visited.insert(v.clone()) {
+                continue;
+            }
+
+            if let Some(neighbors) = self.graph.edges(&v) {
+                let visited = &self.visited;
+                self.stack.extend(
+                    neighbors
+                        .iter()
+                        .filter(|neighbor| !visited.contains(neighbor))
+                        .cloned(),
+                );
+            }
+
+            return Some(v);
+        }
+
+        None
+    }
+}
+
 impl<V> Graph<V>
 where
     V: Hash + Debug + Eq + Clone,
@@ -28,6 +96,27 @@ where
             .insert(to);
     }
 
+    /// Adds both `a -> b` and `b -> a`.
+    pub fn add_undirected_edge(&mut self, a: V, b: V) {
+        self.add_edge(a.clone(), b.clone());
+        self.add_edge(b, a);
+    }
+
+    /// Removes the edge `from -> to`, returns whether it existed.
+    pub fn remove_edge(&mut self, from: &V, to: &V) -> bool {
+        self.adjacency_list
+            .get_mut(from)
+            .is_some_and(|edges| edges.remove(to))
+    }
+
+    /// Removes `v` along with every edge going into or out of it.
+    pub fn remove_vertex(&mut self, v: &V) {
+        self.adjacency_list.remove(v);
+        for edges in self.adjacency_list.values_mut() {
+            edges.remove(v);
+        }
+    }
+
     pub fn are_connected(&self, v1: &V, v2: &V) -> bool {
         self.adjacency_list
             .get(v1)
@@ -39,12 +128,21 @@ where
         self.adjacency_list.get(vertex)
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = (&V, &HashSet<V>)> {
-        self.adjacency_list.iter()
+    /// Number of edges going out of `v`.
+    pub fn out_degree(&self, v: &V) -> usize {
+        self.edges(v).map_or(0, HashSet::len)
     }
 
-    pub fn into_iter(self) -> impl IntoIterator<Item = (V, HashSet<V>)> {
-        self.adjacency_list.into_iter()
+    /// Number of edges going into `v`, this scans all adjacency sets.
+    pub fn in_degree(&self, v: &V) -> usize {
+        self.adjacency_list
+            .values()
+            .filter(|edges| edges.contains(v))
+            .count()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&V, &HashSet<V>)> {
+        self.adjacency_list.iter()
     }
 
     /// Returns a map of paths from vertex `start` to each other vertex in the graph.
@@ -91,4 +189,524 @@ where
 
         layers
     }
+
+    /// Maps each vertex reachable from `start` to the vertex it was first discovered from
+    /// (`None` for `start` itself). Following the chain back to `start` gives a shortest path.
+    pub fn bfs_with_predecessors(&self, start: V) -> HashMap<V, Option<V>> {
+        let mut predecessors = HashMap::new();
+        predecessors.insert(start.clone(), None);
+
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+
+        while let Some(v) = queue.pop_front() {
+            if let Some(neighbors) = self.adjacency_list.get(&v) {
+                for neighbor in neighbors.iter() {
+                    if !predecessors.contains_key(neighbor) {
+                        predecessors.insert(neighbor.clone(), Some(v.clone()));
+                        queue.push_back(neighbor.clone());
+                    }
+                }
+            }
+        }
+
+        predecessors
+    }
+
+    /// Partitions all vertices (including ones that only appear as edge targets)
+    /// into connected components, ignoring edge direction.
+    pub fn connected_components(&self) -> Vec<HashSet<V>> {
+        let mut neighbors: HashMap<&V, Vec<&V>> = HashMap::new();
+        for (v, edges) in self.adjacency_list.iter() {
+            neighbors.entry(v).or_default();
+            for neighbor in edges.iter() {
+                neighbors.entry(v).or_default().push(neighbor);
+                neighbors.entry(neighbor).or_default().push(v);
+            }
+        }
+
+        let mut visited: HashSet<&V> = HashSet::new();
+        let mut components = vec![];
+
+        for &v in neighbors.keys() {
+            if !visited.insert(v) {
+                continue;
+            }
+
+            let mut component = HashSet::new();
+            let mut queue = VecDeque::new();
+            queue.push_back(v);
+
+            while let Some(current) = queue.pop_front() {
+                component.insert(current.clone());
+                for &neighbor in neighbors[current].iter() {
+                    if visited.insert(neighbor) {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// Orders the vertices so every edge goes from an earlier vertex to a later one (Kahn's algorithm).
+    pub fn topological_sort(&self) -> Result<Vec<V>, CycleError<V>> {
+        let mut in_degrees: HashMap<&V, usize> = HashMap::new();
+        for (v, edges) in self.adjacency_list.iter() {
+            in_degrees.entry(v).or_insert(0);
+            for neighbor in edges.iter() {
+                *in_degrees.entry(neighbor).or_insert(0) += 1;
+            }
+        }
+
+        let mut queue: VecDeque<&V> = in_degrees
+            .iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&v, _)| v)
+            .collect();
+
+        let mut order = Vec::with_capacity(in_degrees.len());
+
+        while let Some(v) = queue.pop_front() {
+            order.push(v.clone());
+            if let Some(neighbors) = self.adjacency_list.get(v) {
+                for neighbor in neighbors.iter() {
+                    let degree = in_degrees
+                        .get_mut(neighbor)
+                        .expect("all vertices are counted");
+                    *degree -= 1;
+                    if *degree == 0 {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+
+        if order.len() == in_degrees.len() {
+            return Ok(order);
+        }
+
+        // Every vertex left over still has an incoming edge from another leftover vertex,
+        // so walking those edges backwards must eventually loop.
+        let mut predecessors: HashMap<&V, &V> = HashMap::new();
+        for (v, edges) in self.adjacency_list.iter() {
+            if in_degrees[v] == 0 {
+                continue;
+            }
+            for neighbor in edges.iter() {
+                if in_degrees[neighbor] > 0 {
+                    predecessors.insert(neighbor, v);
+                }
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let mut current = *predecessors.keys().next().expect("a cycle exists");
+        while seen.insert(current) {
+            current = predecessors[current];
+        }
+
+        Err(CycleError {
+            vertex: current.clone(),
+        })
+    }
+
+    /// Whether the (directed) graph contains a cycle.
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    /// Returns the vertices of some cycle in the graph, in edge order.
+    /// The last vertex has an edge back to the first one.
+    pub fn find_cycle(&self) -> Option<Vec<V>> {
+        let mut states = HashMap::new();
+        let mut path = vec![];
+
+        for v in self.adjacency_list.keys() {
+            if !states.contains_key(v) {
+                if let Some(cycle) = self.find_cycle_from(v, &mut states, &mut path) {
+                    return Some(cycle);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn find_cycle_from<'a>(
+        &'a self,
+        v: &'a V,
+        states: &mut HashMap<&'a V, VisitState>,
+        path: &mut Vec<&'a V>,
+    ) -> Option<Vec<V>> {
+        states.insert(v, VisitState::InProgress);
+        path.push(v);
+
+        if let Some(neighbors) = self.adjacency_list.get(v) {
+            for neighbor in neighbors.iter() {
+                match states.get(neighbor) {
+                    Some(VisitState::InProgress) => {
+                        let start = path
+                            .iter()
+                            .position(|&p| p == neighbor)
+                            .expect("vertices in progress are on the path");
+                        return Some(path[start..].iter().map(|&p| p.clone()).collect());
+                    }
+                    Some(VisitState::Done) => {}
+                    None => {
+                        if let Some(cycle) = self.find_cycle_from(neighbor, states, path) {
+                            return Some(cycle);
+                        }
+                    }
+                }
+            }
+        }
+
+        path.pop();
+        states.insert(v, VisitState::Done);
+
+        None
+    }
+
+    /// Visits every vertex reachable from `start` in depth-first preorder.
+    /// Edges are kept in a `HashSet`, so the order in which siblings are visited is unspecified.
+    pub fn dfs(&self, start: V) -> impl Iterator<Item = V> + '_ {
+        Dfs {
+            graph: self,
+            stack: vec![start],
+            visited: HashSet::new(),
+        }
+    }
+
+    /// Returns the vertices along a shortest path from `start` to `end`, both ends included.
+    pub fn shortest_path(&self, start: &V, end: &V) -> Option<Vec<V>> {
+        let mut predecessors: HashMap<&V, Option<&V>> = HashMap::new();
+        predecessors.insert(start, None);
+
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+
+        while let Some(v) = queue.pop_front() {
+            if v == end {
+                let mut path = vec![v.clone()];
+                let mut current = v;
+                while let Some(&Some(previous)) = predecessors.get(current) {
+                    path.push(previous.clone());
+                    current = previous;
+                }
+                path.reverse();
+
+                return Some(path);
+            }
+
+            if let Some(neighbors) = self.adjacency_list.get(v) {
+                for neighbor in neighbors.iter() {
+                    if !predecessors.contains_key(neighbor) {
+                        predecessors.insert(neighbor, Some(v));
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<V> IntoIterator for Graph<V>
+where
+    V: Hash + Debug + Eq + Clone,
+{
+    type Item = (V, HashSet<V>);
+    type IntoIter = std::collections::hash_map::IntoIter<V, HashSet<V>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.adjacency_list.into_iter()
+    }
+}
+
+impl<'a, V> IntoIterator for &'a Graph<V>
+where
+    V: Hash + Debug + Eq + Clone,
+{
+    type Item = (&'a V, &'a HashSet<V>);
+    type IntoIter = std::collections::hash_map::Iter<'a, V, HashSet<V>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.adjacency_list.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degrees() {
+        let mut g = Graph::new();
+        g.add_edge("A", "B");
+        g.add_edge("A", "C");
+        g.add_edge("C", "B");
+        g.add_edge("B", "D");
+
+        assert_eq!(g.out_degree(&"A"), 2);
+        assert_eq!(g.in_degree(&"A"), 0);
+        assert_eq!(g.out_degree(&"B"), 1);
+        assert_eq!(g.in_degree(&"B"), 2);
+        assert_eq!(g.out_degree(&"D"), 0);
+        assert_eq!(g.in_degree(&"D"), 1);
+    }
+
+    #[test]
+    fn test_degrees_edge_cases() {
+        let mut g = Graph::new();
+        g.add_edge("A", "A");
+        g.add_edge("A", "B");
+        g.add_edge("A", "B");
+
+        // A self loop counts both ways, duplicate edges are only counted once.
+        assert_eq!(g.out_degree(&"A"), 2);
+        assert_eq!(g.in_degree(&"A"), 1);
+        assert_eq!(g.in_degree(&"B"), 1);
+
+        assert_eq!(g.out_degree(&"Z"), 0);
+        assert_eq!(g.in_degree(&"Z"), 0);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut g = Graph::new();
+        g.add_edge("A", "B");
+        g.add_edge("A", "C");
+        g.add_edge("B", "C");
+
+        let mut edges = 0;
+        for (_, neighbors) in &g {
+            edges += neighbors.len();
+        }
+        assert_eq!(edges, 3);
+
+        let mut vertices = vec![];
+        for (v, neighbors) in g {
+            assert!(!neighbors.is_empty());
+            vertices.push(v);
+        }
+        vertices.sort_unstable();
+        assert_eq!(vertices, vec!["A", "B"]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut g = Graph::new();
+        g.add_edge("COM".to_owned(), "B".to_owned());
+        g.add_edge("B".to_owned(), "C".to_owned());
+        g.add_edge("B".to_owned(), "D".to_owned());
+
+        let json = serde_json::to_string(&g).unwrap();
+        let restored: Graph<String> = serde_json::from_str(&json).unwrap();
+
+        for (v, neighbors) in &g {
+            for neighbor in neighbors {
+                assert!(restored.are_connected(v, neighbor));
+            }
+        }
+        assert_eq!(restored.iter().count(), g.iter().count());
+        assert!(!restored.are_connected(&"C".to_owned(), &"B".to_owned()));
+    }
+
+    #[test]
+    fn test_add_undirected_edge() {
+        let mut g = Graph::new();
+        g.add_undirected_edge("A", "B");
+
+        assert!(g.are_connected(&"A", &"B"));
+        assert!(g.are_connected(&"B", &"A"));
+        assert!(!g.are_connected(&"A", &"A"));
+    }
+
+    #[test]
+    fn test_remove_edge() {
+        let mut g = Graph::new();
+        g.add_edge("A", "B");
+        g.add_edge("B", "A");
+
+        assert!(g.remove_edge(&"A", &"B"));
+        assert!(!g.remove_edge(&"A", &"B"));
+        assert!(!g.remove_edge(&"C", &"A"));
+
+        assert!(!g.are_connected(&"A", &"B"));
+        assert!(g.are_connected(&"B", &"A"));
+    }
+
+    #[test]
+    fn test_remove_vertex() {
+        let mut g = Graph::new();
+        g.add_edge("A", "B");
+        g.add_edge("B", "C");
+        g.add_edge("C", "A");
+        g.add_edge("C", "B");
+
+        g.remove_vertex(&"B");
+
+        assert!(g.edges(&"B").is_none());
+        assert!(!g.are_connected(&"A", &"B"));
+        assert!(!g.are_connected(&"C", &"B"));
+        assert!(g.are_connected(&"C", &"A"));
+        assert_eq!(g.in_degree(&"B"), 0);
+    }
+
+    #[test]
+    fn test_bfs_with_predecessors() {
+        let mut g = Graph::new();
+        g.add_edge("A", "B");
+        g.add_edge("B", "C");
+        g.add_edge("C", "D");
+        g.add_edge("A", "C");
+        g.add_edge("X", "A");
+
+        let predecessors = g.bfs_with_predecessors("A");
+
+        assert_eq!(predecessors.len(), 4);
+        assert_eq!(predecessors["A"], None);
+        assert_eq!(predecessors["B"], Some("A"));
+        assert_eq!(predecessors["C"], Some("A"));
+        assert_eq!(predecessors["D"], Some("C"));
+        assert!(!predecessors.contains_key("X"));
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let mut g = Graph::new();
+        g.add_edge("A", "B");
+        g.add_edge("B", "C");
+        g.add_edge("C", "D");
+        g.add_edge("A", "C");
+        g.add_edge("X", "A");
+
+        assert_eq!(g.shortest_path(&"A", &"D"), Some(vec!["A", "C", "D"]));
+        assert_eq!(g.shortest_path(&"D", &"A"), None);
+        assert_eq!(g.shortest_path(&"A", &"Y"), None);
+        assert_eq!(g.shortest_path(&"B", &"B"), Some(vec!["B"]));
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let mut g = Graph::new();
+        g.add_edge("A", "B");
+        g.add_edge("B", "C");
+        g.add_edge("C", "A");
+        g.add_edge("X", "Y");
+        g.add_edge("Z", "Y");
+        g.add_edge("X", "Z");
+        g.add_edge("I", "I");
+        g.add_edge("J", "K");
+        g.remove_edge(&"J", &"K");
+
+        let mut components: Vec<Vec<&str>> = g
+            .connected_components()
+            .into_iter()
+            .map(|component| {
+                let mut component: Vec<&str> = component.into_iter().collect();
+                component.sort_unstable();
+                component
+            })
+            .collect();
+        components.sort_unstable();
+
+        assert_eq!(
+            components,
+            vec![
+                vec!["A", "B", "C"],
+                vec!["I"],
+                vec!["J"],
+                vec!["X", "Y", "Z"]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let mut g = Graph::new();
+        g.add_edge("ORE", "A");
+        g.add_edge("ORE", "B");
+        g.add_edge("A", "C");
+        g.add_edge("B", "C");
+        g.add_edge("C", "FUEL");
+        g.add_edge("A", "FUEL");
+
+        let order = g.topological_sort().unwrap();
+        assert_eq!(order.len(), 5);
+
+        let position = |v: &str| order.iter().position(|&o| o == v).unwrap();
+        for (v, edges) in g.iter() {
+            for neighbor in edges {
+                assert!(position(v) < position(neighbor), "{} -> {}", v, neighbor);
+            }
+        }
+    }
+
+    #[test]
+    fn test_topological_sort_cycle() {
+        let mut g = Graph::new();
+        g.add_edge("X", "A");
+        g.add_edge("A", "B");
+        g.add_edge("B", "C");
+        g.add_edge("C", "A");
+        g.add_edge("C", "D");
+
+        let err = g.topological_sort().unwrap_err();
+        assert!(["A", "B", "C"].contains(&err.vertex), "{}", err);
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let mut g = Graph::new();
+        g.add_edge("A", "B");
+        g.add_edge("A", "C");
+        g.add_edge("B", "D");
+        g.add_edge("C", "D");
+        assert!(!g.has_cycle());
+        assert_eq!(g.find_cycle(), None);
+
+        g.add_edge("D", "E");
+        g.add_edge("E", "F");
+        g.add_edge("F", "D");
+        assert!(g.has_cycle());
+
+        let mut cycle = g.find_cycle().unwrap();
+        assert_eq!(cycle.len(), 3);
+        for (i, v) in cycle.iter().enumerate() {
+            assert!(g.are_connected(v, &cycle[(i + 1) % cycle.len()]));
+        }
+
+        cycle.sort_unstable();
+        assert_eq!(cycle, vec!["D", "E", "F"]);
+    }
+
+    #[test]
+    fn test_dfs() {
+        let mut g = Graph::new();
+        g.add_edge("A", "B");
+        g.add_edge("B", "C");
+        g.add_edge("C", "A");
+        g.add_edge("A", "D");
+        g.add_edge("D", "E");
+        g.add_edge("X", "A");
+
+        let visited: Vec<&str> = g.dfs("A").collect();
+        assert_eq!(visited[0], "A");
+        assert_eq!(visited.len(), 5);
+
+        let visited: HashSet<&str> = visited.into_iter().collect();
+        let reachable: HashSet<&str> = g.bfs("A").into_values().flatten().collect();
+        assert_eq!(visited, reachable);
+
+        // Preorder, so a vertex's descendants come right after it.
+        let visited: Vec<&str> = g.dfs("D").collect();
+        assert_eq!(visited, vec!["D", "E"]);
+    }
 }
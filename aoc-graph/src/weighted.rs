@@ -0,0 +1,226 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::Add;
+
+/// A directed graph where every edge has a cost of type `W`.
+/// Path searches assume weights are non-negative, with `W::default()` being zero.
+#[derive(Debug)]
+pub struct WeightedGraph<V, W>
+where
+    V: Hash + Debug + Eq + Clone,
+    W: Ord + Add<Output = W> + Default + Copy,
+{
+    adjacency_list: HashMap<V, HashMap<V, W>>,
+}
+
+impl<V, W> WeightedGraph<V, W>
+where
+    V: Hash + Debug + Eq + Clone,
+    W: Ord + Add<Output = W> + Default + Copy,
+{
+    pub fn new() -> Self {
+        WeightedGraph {
+            adjacency_list: Default::default(),
+        }
+    }
+
+    /// Adds an edge, replacing the weight of an existing edge between the same vertices.
+    pub fn add_edge(&mut self, from: V, to: V, weight: W) {
+        self.adjacency_list
+            .entry(from)
+            .or_default()
+            .insert(to, weight);
+    }
+
+    pub fn weight(&self, from: &V, to: &V) -> Option<W> {
+        self.adjacency_list
+            .get(from)
+            .and_then(|edges| edges.get(to))
+            .copied()
+    }
+
+    pub fn are_connected(&self, from: &V, to: &V) -> bool {
+        self.weight(from, to).is_some()
+    }
+
+    pub fn edges(&self, vertex: &V) -> Option<&HashMap<V, W>> {
+        self.adjacency_list.get(vertex)
+    }
+
+    /// Number of edges going out of `v`.
+    pub fn out_degree(&self, v: &V) -> usize {
+        self.edges(v).map_or(0, HashMap::len)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&V, &HashMap<V, W>)> {
+        self.adjacency_list.iter()
+    }
+
+    /// Returns the minimal cost of reaching each vertex reachable from `start`.
+    pub fn dijkstra(&self, start: V) -> HashMap<V, W> {
+        let mut costs = HashMap::new();
+
+        // `BinaryHeap` is a max-heap, and `V` isn't necessarily `Ord`,
+        // so we keep an index into `vertices` in the heap instead of the vertex itself.
+        let mut vertices = vec![start];
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((W::default(), 0)));
+
+        while let Some(Reverse((cost, index))) = heap.pop() {
+            let v = &vertices[index];
+            if costs.contains_key(v) {
+                continue;
+            }
+            costs.insert(v.clone(), cost);
+
+            if let Some(edges) = self.adjacency_list.get(v) {
+                for (neighbor, weight) in edges.iter() {
+                    if !costs.contains_key(neighbor) {
+                        heap.push(Reverse((cost + *weight, vertices.len())));
+                        vertices.push(neighbor.clone());
+                    }
+                }
+            }
+        }
+
+        costs
+    }
+
+    /// Finds the cheapest path from `start` to `goal`, returning its cost and vertices.
+    ///
+    /// `heuristic` estimates the remaining cost to `goal`, it must be admissible
+    /// (never overestimate the real cost), otherwise the returned path may not be optimal.
+    pub fn astar<H: Fn(&V) -> W>(&self, start: V, goal: V, heuristic: H) -> Option<(W, Vec<V>)> {
+        // Same trick as in `dijkstra`, the heap holds indices into `vertices`.
+        // `previous` holds the index of the entry each entry was reached from.
+        let mut vertices = vec![start];
+        let mut previous: Vec<Option<usize>> = vec![None];
+        let mut best: HashMap<V, W> = HashMap::new();
+        best.insert(vertices[0].clone(), W::default());
+
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((heuristic(&vertices[0]), W::default(), 0)));
+
+        while let Some(Reverse((_, cost, index))) = heap.pop() {
+            let v = &vertices[index];
+
+            if *v == goal {
+                let mut path = vec![v.clone()];
+                let mut current = index;
+                while let Some(p) = previous[current] {
+                    path.push(vertices[p].clone());
+                    current = p;
+                }
+                path.reverse();
+
+                return Some((cost, path));
+            }
+
+            // A cheaper way to this vertex was already found.
+            if best.get(v).is_some_and(|&b| b < cost) {
+                continue;
+            }
+
+            if let Some(edges) = self.adjacency_list.get(v) {
+                for (neighbor, weight) in edges.iter() {
+                    let neighbor_cost = cost + *weight;
+                    if best.get(neighbor).is_none_or(|&b| neighbor_cost < b) {
+                        best.insert(neighbor.clone(), neighbor_cost);
+                        heap.push(Reverse((
+                            neighbor_cost + heuristic(neighbor),
+                            neighbor_cost,
+                            vertices.len(),
+                        )));
+                        vertices.push(neighbor.clone());
+                        previous.push(Some(index));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<V, W> Default for WeightedGraph<V, W>
+where
+    V: Hash + Debug + Eq + Clone,
+    W: Ord + Add<Output = W> + Default + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weights() {
+        let mut g: WeightedGraph<&str, u32> = WeightedGraph::new();
+        g.add_edge("A", "B", 3);
+        g.add_edge("A", "C", 4);
+        g.add_edge("A", "B", 5);
+
+        assert_eq!(g.weight(&"A", &"B"), Some(5));
+        assert_eq!(g.weight(&"A", &"C"), Some(4));
+        assert_eq!(g.weight(&"B", &"A"), None);
+        assert!(g.are_connected(&"A", &"C"));
+        assert!(!g.are_connected(&"C", &"A"));
+        assert_eq!(g.out_degree(&"A"), 2);
+        assert_eq!(g.iter().count(), 1);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let mut g = WeightedGraph::new();
+        g.add_edge("A", "B", 1);
+        g.add_edge("B", "C", 2);
+        g.add_edge("A", "C", 10);
+        g.add_edge("X", "A", 1);
+
+        let costs = g.dijkstra("A");
+
+        assert_eq!(costs.len(), 3);
+        assert_eq!(costs["A"], 0);
+        assert_eq!(costs["B"], 1);
+        assert_eq!(costs["C"], 3);
+        assert_eq!(g.weight(&"A", &"C"), Some(10));
+    }
+
+    #[test]
+    fn test_astar() {
+        // A 5x5 grid where moving right is cheap, and the direct route down is expensive.
+        let mut g = WeightedGraph::new();
+        for x in 0..5i64 {
+            for y in 0..5i64 {
+                if x < 4 {
+                    g.add_edge((x, y), (x + 1, y), 1);
+                    g.add_edge((x + 1, y), (x, y), 1);
+                }
+                if y < 4 {
+                    let weight = if x == 0 { 10 } else { 1 };
+                    g.add_edge((x, y), (x, y + 1), weight);
+                    g.add_edge((x, y + 1), (x, y), weight);
+                }
+            }
+        }
+
+        let manhattan = |&(x, y): &(i64, i64)| (x.abs() + (4 - y).abs()) as u64;
+
+        let (cost, path) = g.astar((0, 0), (0, 4), |_| 0).unwrap();
+        let (heuristic_cost, heuristic_path) = g.astar((0, 0), (0, 4), manhattan).unwrap();
+
+        assert_eq!(cost, 6);
+        assert_eq!(heuristic_cost, cost);
+        assert_eq!(path.len(), heuristic_path.len());
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(0, 4)));
+
+        assert_eq!(g.astar((0, 0), (0, 0), manhattan), Some((0, vec![(0, 0)])));
+        assert_eq!(g.astar((0, 0), (9, 9), |_| 0), None);
+    }
+}
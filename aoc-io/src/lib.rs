@@ -0,0 +1,92 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::io::{stdin, Read};
+use std::path::{Path, PathBuf};
+
+/// Name of the input file (under `dayN/input`) of each day, starting at day 1.
+const INPUT_FILES: [&str; 14] = [
+    "modules",
+    "opcodes",
+    "wires",
+    "range",
+    "opcodes",
+    "orbital_map",
+    "opcodes",
+    "image",
+    "opcodes",
+    "astroid_map",
+    "opcodes",
+    "moons",
+    "opcodes",
+    "opcodes",
+];
+
+/// Reads all of stdin.
+pub fn read_stdin() -> Result<String> {
+    let mut input = String::new();
+
+    stdin()
+        .read_to_string(&mut input)
+        .context("Failed to read input from stdin")?;
+
+    Ok(input)
+}
+
+/// Path of the input of `day`, relative to the workspace at `root`.
+pub fn input_path(root: &Path, day: u32) -> Result<PathBuf> {
+    let file = match day.checked_sub(1).and_then(|i| INPUT_FILES.get(i as usize)) {
+        Some(file) => file,
+        None => bail!("There is no input for day {}", day),
+    };
+
+    Ok(root.join(format!("day{}", day)).join("input").join(file))
+}
+
+/// Reads the input of `day` from the workspace at `root`.
+pub fn read_input_from(root: &Path, day: u32) -> Result<String> {
+    let path = input_path(root, day)?;
+    fs::read_to_string(&path).with_context(|| format!("Failed to read `{}`", path.display()))
+}
+
+/// Reads the committed input of `day`.
+pub fn read_input(day: u32) -> Result<String> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .context("aoc-io is expected to be inside the workspace")?;
+
+    read_input_from(root, day)
+}
+
+/// Like `read_input`, but returns `None` when the input is not checked out,
+/// so tests depending on it can be skipped.
+pub fn try_read_input(day: u32) -> Option<String> {
+    read_input(day).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_input_from_fixture() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+
+        assert_eq!(read_input_from(&fixtures, 1).unwrap(), "12\n14\n1969\n");
+        assert!(read_input_from(&fixtures, 2).is_err());
+    }
+
+    #[test]
+    fn test_input_path() {
+        let path = input_path(Path::new("root"), 8).unwrap();
+        let expected: PathBuf = ["root", "day8", "input", "image"].iter().collect();
+        assert_eq!(path, expected);
+
+        assert!(input_path(Path::new("root"), 0).is_err());
+        assert!(input_path(Path::new("root"), 15).is_err());
+    }
+
+    #[test]
+    fn test_try_read_input() {
+        assert!(try_read_input(15).is_none());
+    }
+}
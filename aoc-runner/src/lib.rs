@@ -0,0 +1,130 @@
+use anyhow::{bail, Result};
+use day10::Position;
+use day13::FollowBall;
+use std::fmt;
+
+/// The answer to a puzzle, days return all sorts of types.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+}
+
+impl Answer {
+    /// Multi-line answers are pictures that need to be read.
+    pub fn is_picture(&self) -> bool {
+        match self {
+            Answer::Integer(_) => false,
+            Answer::Text(text) => text.contains('\n'),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Answer::Integer(n as i128)
+            }
+        })*
+    };
+}
+
+impl_from_integer!(i32, u32, i64, u64, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+/// Intcode diagnostic outputs.
+impl From<Vec<i64>> for Answer {
+    fn from(outputs: Vec<i64>) -> Self {
+        Answer::Text(format!("{:?}", outputs))
+    }
+}
+
+/// An asteroid and the number it sees, or its place in the vaporization order.
+impl From<(Position, usize)> for Answer {
+    fn from(asteroid: (Position, usize)) -> Self {
+        Answer::Text(format!("{:?}", asteroid))
+    }
+}
+
+/// Solves `part` of `day` for the given input.
+pub fn solve(day: u32, part: u32, input: &str) -> Result<Answer> {
+    let answer: Answer = match (day, part) {
+        (1, 1) => day1::part_1(input)?.into(),
+        (1, 2) => day1::part_2(input)?.into(),
+        (2, 1) => day2::part_1(input)?.into(),
+        (2, 2) => day2::part_2_answer(input)?.into(),
+        (3, 1) => day3::part_1(input)?.into(),
+        (3, 2) => day3::part_2(input)?.into(),
+        (4, 1) => {
+            let (low, hi) = day4::parse_range(input)?;
+            day4::part_1(low, hi).into()
+        }
+        (4, 2) => {
+            let (low, hi) = day4::parse_range(input)?;
+            day4::part_2(low, hi).into()
+        }
+        (5, 1) => day5::part_1(input)?.into(),
+        (5, 2) => day5::part_2(input)?.into(),
+        (6, 1) => day6::part_1(input)?.into(),
+        (6, 2) => day6::part_2(input)?.into(),
+        (7, 1) => day7::part_1(input)?.into(),
+        (7, 2) => day7::part_2(input)?.into(),
+        (8, 1) => day8::part_1(input)?.into(),
+        (8, 2) => day8::part_2(input)?.into(),
+        (9, 1) => day9::part_1(input)?.into(),
+        (9, 2) => day9::part_2(input)?.into(),
+        (10, 1) => day10::part_1(input)?.into(),
+        (10, 2) => day10::part_2(input, 200)?.into(),
+        (11, 1) => day11::part_1(input)?.into(),
+        (11, 2) => day11::part_2(input)?.into(),
+        (12, 1) => day12::part_1(input)?.into(),
+        (12, 2) => day12::part_2(input)?.into(),
+        (13, 1) => day13::part_1(input)?.into(),
+        (13, 2) => day13::part_2(input, false, &mut FollowBall)?.into(),
+        (14, 1) => day14::part_1(input)?.into(),
+        (14, 2) => day14::part_2(input)?.into(),
+        (1..=14, _) => bail!("Day {} has no part {}", day, part),
+        _ => bail!("Day {} is not solved", day),
+    };
+
+    Ok(answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(solve(1, 1, "12\n14\n1969\n").unwrap(), Answer::Integer(658));
+        assert_eq!(solve(4, 1, "111110-111112").unwrap(), Answer::Integer(2));
+
+        assert!(solve(1, 3, "12").is_err());
+        assert!(solve(15, 1, "").is_err());
+    }
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(Answer::from(42_usize).to_string(), "42");
+        assert_eq!(Answer::from(vec![0, 0, 7]).to_string(), "[0, 0, 7]");
+        assert_eq!(Answer::from(((1, 2), 3)).to_string(), "((1, 2), 3)");
+
+        assert!(!Answer::from(42_u64).is_picture());
+        assert!(Answer::from("#.\n.#".to_owned()).is_picture());
+    }
+}
@@ -0,0 +1,92 @@
+use anyhow::{bail, Context, Result};
+use aoc_io::read_input;
+use aoc_runner::solve;
+use serde_json::json;
+use std::env;
+use std::fs;
+use std::time::Instant;
+
+const USAGE: &str = "Usage: aoc-runner <day> [part] [--input <path>] [--json]";
+
+struct Args {
+    day: u32,
+    part: Option<u32>,
+    input: Option<String>,
+    /// Print a JSON object per part instead of the human readable line.
+    json: bool,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Args> {
+        let mut positional = vec![];
+        let mut input = None;
+        let mut json = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" => input = Some(args.next().context(USAGE)?),
+                "--json" => json = true,
+                _ => positional.push(arg),
+            }
+        }
+
+        let (day, part) = match positional.as_slice() {
+            [day] => (day, None),
+            [day, part] => (day, Some(part)),
+            _ => bail!(USAGE),
+        };
+
+        Ok(Args {
+            day: day
+                .parse()
+                .with_context(|| format!("Invalid day `{}`", day))?,
+            part: part
+                .map(|part| part.parse())
+                .transpose()
+                .with_context(|| format!("Invalid part `{}`", part.unwrap()))?,
+            input,
+            json,
+        })
+    }
+}
+
+fn main() -> Result<()> {
+    env_logger::init();
+
+    let args = Args::parse(env::args().skip(1))?;
+
+    let input = match &args.input {
+        Some(path) => {
+            fs::read_to_string(path).with_context(|| format!("Failed to read `{}`", path))?
+        }
+        None => read_input(args.day)?,
+    };
+
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+
+    for part in parts {
+        let now = Instant::now();
+        let answer = solve(args.day, part, &input)?;
+        let elapsed = now.elapsed();
+
+        if args.json {
+            let report = json!({
+                "day": args.day,
+                "part": part,
+                "answer": answer.to_string(),
+                "elapsed_ms": elapsed.as_secs_f64() * 1000.0,
+            });
+            println!("{}", report);
+        } else if answer.is_picture() {
+            // Pictures go on their own lines.
+            println!("Part {} in {:?}:\n{}", part, elapsed, answer);
+        } else {
+            println!("Part {} - {} in {:?}", part, answer, elapsed);
+        }
+    }
+
+    Ok(())
+}
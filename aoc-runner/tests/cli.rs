@@ -0,0 +1,61 @@
+use serde_json::Value;
+use std::process::Command;
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/day1");
+
+fn run(args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc-runner"))
+        .args(args)
+        .output()
+        .unwrap();
+
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn test_day_1_fixture() {
+    let (success, stdout) = run(&["1", "--input", FIXTURE]);
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert!(success);
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("Part 1 - 34241 in "));
+    assert!(lines[1].starts_with("Part 2 - 51316 in "));
+}
+
+#[test]
+fn test_single_part() {
+    let (success, stdout) = run(&["1", "2", "--input", FIXTURE]);
+
+    assert!(success);
+    assert!(stdout.starts_with("Part 2 - 51316 in "));
+    assert_eq!(stdout.lines().count(), 1);
+}
+
+#[test]
+fn test_unknown_day() {
+    let (success, _) = run(&["42", "--input", FIXTURE]);
+    assert!(!success);
+}
+
+#[test]
+fn test_json_output() {
+    let (success, stdout) = run(&["1", "--json", "--input", FIXTURE]);
+    assert!(success);
+
+    let reports: Vec<Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0]["day"], 1);
+    assert_eq!(reports[0]["part"], 1);
+    assert_eq!(reports[0]["answer"], "34241");
+    assert!(reports[0]["elapsed_ms"].as_f64().unwrap() >= 0.0);
+    assert_eq!(reports[1]["part"], 2);
+    assert_eq!(reports[1]["answer"], "51316");
+}
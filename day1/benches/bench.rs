@@ -1,8 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use std::fs;
 
 fn benchmark(c: &mut Criterion) {
-    let s = fs::read_to_string(r#"input\modules"#).unwrap();
+    let s = aoc_io::read_input(1).unwrap();
 
     c.bench_function("2019 day 1 part one", |b| {
         b.iter(|| day1::part_1(&s));
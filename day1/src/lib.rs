@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use std::io::BufRead;
 
 /// Fuel required to launch a given module is based on its mass.
 /// Specifically, to find the fuel required for a module, take its mass, divide by three, round down, and subtract 2.
@@ -7,33 +8,71 @@ fn calculate_fuel(module_mass: usize) -> usize {
     (module_mass / 3).saturating_sub(2)
 }
 
+/// Yields the fuel needed for `module_mass`, then the fuel needed for that fuel, and so on,
+/// stopping once no more fuel is required.
+pub fn fuel_sequence(module_mass: usize) -> impl Iterator<Item = usize> {
+    std::iter::successors(Some(module_mass), |&mass| match calculate_fuel(mass) {
+        0 => None,
+        fuel => Some(fuel),
+    })
+    .skip(1)
+}
+
 /// Fuel itself requires fuel just like a module - take its mass, divide by three, round down, and subtract 2.
 /// However, that fuel also requires fuel, and that fuel requires fuel, and so on.
 /// the remaining mass, if any, is instead handled by wishing really hard, which has no mass and is outside the scope of this calculation.
 fn calculate_fuel_recursively(module_mass: usize) -> usize {
-    let mut total_fuel = 0;
-
-    let mut fuel = calculate_fuel(module_mass);
+    fuel_sequence(module_mass).sum()
+}
 
-    while fuel > 0 {
-        total_fuel += fuel;
-        fuel = calculate_fuel(fuel)
+/// Parses a single module mass, `None` for blank lines.
+fn parse_mass(line: &str) -> Option<Result<usize>> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
     }
 
-    total_fuel
+    Some(
+        line.parse::<usize>()
+            .with_context(|| format!("Failed to convert `{}` to a numerical value.", line)),
+    )
 }
 
 fn fuel_for_modules(input: &str, fuel_calculator: impl Fn(usize) -> usize + Copy) -> Result<usize> {
     input
         .lines()
-        .map(|line| {
-            line.parse::<usize>()
-                .with_context(|| format!("Failed to convert `{}` to a numerical value.", line))
-                .map(fuel_calculator)
-        })
+        .filter_map(parse_mass)
+        .map(|mass| mass.map(fuel_calculator))
         .sum()
 }
 
+/// Same as `fuel_for_modules`, but reads the masses line by line, reusing a single buffer.
+fn fuel_for_modules_from_reader(
+    mut reader: impl BufRead,
+    fuel_calculator: impl Fn(usize) -> usize,
+) -> Result<usize> {
+    let mut line = String::new();
+    let mut total = 0;
+
+    while reader
+        .read_line(&mut line)
+        .context("Failed to read input")?
+        > 0
+    {
+        if let Some(mass) = parse_mass(&line) {
+            total += fuel_calculator(mass?);
+        }
+        line.clear();
+    }
+
+    Ok(total)
+}
+
+/// Like `part_1`, but streams the module masses from `reader`.
+pub fn part_1_from_reader<R: BufRead>(reader: R) -> Result<usize> {
+    fuel_for_modules_from_reader(reader, calculate_fuel)
+}
+
 /// What is the sum of fuel required for all of your modules?
 pub fn part_1(input: &str) -> Result<usize> {
     fuel_for_modules(input, calculate_fuel)
@@ -56,3 +95,59 @@ fn test_calculate_fuel() {
 fn test_calculate_fuel_recursively() {
     assert_eq!(calculate_fuel_recursively(100_756), 50346);
 }
+
+#[test]
+fn test_fuel_sequence_matches_loop() {
+    fn calculate_fuel_with_loop(module_mass: usize) -> usize {
+        let mut total_fuel = 0;
+        let mut fuel = calculate_fuel(module_mass);
+
+        while fuel > 0 {
+            total_fuel += fuel;
+            fuel = calculate_fuel(fuel)
+        }
+
+        total_fuel
+    }
+
+    for mass in 0..20_000 {
+        assert_eq!(
+            calculate_fuel_recursively(mass),
+            calculate_fuel_with_loop(mass),
+            "mass {}",
+            mass
+        );
+    }
+
+    assert_eq!(
+        fuel_sequence(1969).collect::<Vec<_>>(),
+        vec![654, 216, 70, 21, 5]
+    );
+}
+
+#[test]
+fn test_part_1_from_reader() {
+    let input = "12\n14\n1969\n100756\n";
+    let fuel = part_1_from_reader(std::io::Cursor::new(input)).unwrap();
+
+    assert_eq!(fuel, 2 + 2 + 654 + 33583);
+    assert_eq!(fuel, part_1(input).unwrap());
+    assert!(part_1_from_reader(std::io::Cursor::new("12\nfoo\n")).is_err());
+}
+
+#[test]
+fn test_blank_lines() {
+    assert_eq!(part_1("12\n14\n").unwrap(), 4);
+    assert_eq!(part_1("12\n\n  \n 14 \n\n").unwrap(), 4);
+    assert_eq!(part_2("1969\n\n").unwrap(), 966);
+    assert_eq!(
+        part_1_from_reader(std::io::Cursor::new("12\n\n14\n\n")).unwrap(),
+        4
+    );
+
+    let err = part_1("12\n\n1a4\n").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Failed to convert `1a4` to a numerical value."
+    );
+}
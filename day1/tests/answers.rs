@@ -0,0 +1,15 @@
+use day1::{part_1, part_2};
+
+#[test]
+fn test_puzzle_answers() {
+    let input = match aoc_io::try_read_input(1) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 1 is missing");
+            return;
+        }
+    };
+
+    assert_eq!(part_1(&input).unwrap(), 3337766);
+    assert_eq!(part_2(&input).unwrap(), 5003788);
+}
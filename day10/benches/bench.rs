@@ -1,15 +1,42 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use std::fs;
+
+/// The largest example from the puzzle, the best location is (11, 13) seeing 210 asteroids.
+const LARGE_EXAMPLE: &str = "
+.#..##.###...#######
+##.############..##.
+.#.######.########.#
+.###.#######.####.#.
+#####.##.#.##.###.##
+..#####..#.#########
+####################
+#.####....###.#.#.##
+##.#################
+#####.##.###..####..
+..######..##.#######
+####.##.####...##..#
+.#####..#.######.###
+##...#.##########...
+#.##########.#######
+.####.#.###.###.#.##
+....##.##.###..#####
+.#.#.###########.###
+#.#.#.#####.####.###
+###.##.####.##.#..##
+";
 
 fn benchmark(c: &mut Criterion) {
-    let s = fs::read_to_string(r#"input/astroid_map"#).unwrap();
+    let s = aoc_io::read_input(10).unwrap();
 
     c.bench_function("2019 day 10 part one", |b| {
         b.iter(|| day10::part_1(&s));
     });
 
+    c.bench_function("2019 day 10 part one (20x20 example)", |b| {
+        b.iter(|| day10::part_1(LARGE_EXAMPLE));
+    });
+
     c.bench_function("2019 day 10 part two", |b| {
-        b.iter(|| day10::part_2(&s));
+        b.iter(|| day10::part_2(&s, 200));
     });
 }
 
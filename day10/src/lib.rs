@@ -1,11 +1,11 @@
 use anyhow::{bail, Context, Result};
-use itertools::Itertools;
+use aoc_coord::Coord;
 use log::debug;
-use std::borrow::Borrow;
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BinaryHeap, HashSet};
 
-type Coord = (i32, i32);
+/// Results are reported as plain `(x, y)` tuples.
+pub type Position = (i32, i32);
 
 fn parse_input(input: &str) -> Vec<Coord> {
     input
@@ -16,7 +16,7 @@ fn parse_input(input: &str) -> Vec<Coord> {
             line.chars()
                 .enumerate()
                 .filter_map(|(x, c)| match c {
-                    '#' => Some((x as i32, y as i32)),
+                    '#' => Some(Coord::new(x as i32, y as i32)),
                     _ => None,
                 })
                 .collect::<Vec<Coord>>()
@@ -25,63 +25,106 @@ fn parse_input(input: &str) -> Vec<Coord> {
         .collect()
 }
 
-fn distance(a: &Coord, b: &Coord) -> f32 {
-    (((a.0 - b.0).pow(2) + (a.1 - b.1).pow(2)) as f32).sqrt()
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
 }
 
-fn angle<T: Borrow<Coord>>(a: T, b: T) -> f32 {
-    let (a, b) = (a.borrow(), b.borrow());
-    let dx = (a.0 - b.0) as f32;
-    let dy = (a.1 - b.1) as f32;
-    dx.atan2(dy)
+/// Divides a direction vector by the gcd of its components, preserving their signs.
+/// Two asteroids are collinear with the origin (in the same direction) iff their directions reduce to the same vector.
+pub fn reduce_direction(dx: i32, dy: i32) -> (i32, i32) {
+    match gcd(dx, dy) {
+        0 => (0, 0),
+        divisor => (dx / divisor, dy / divisor),
+    }
 }
 
-/// Absolute angle relative to "up" direction of canon.
-fn angle_abs<T: Borrow<Coord>>(a: T, b: T) -> f32 {
-    let (a, b) = (a.borrow(), b.borrow());
-    let dx = (a.0 - b.0) as f32;
-    let dy = (a.1 - b.1) as f32;
-    let atan = dx.atan2(dy);
+/// Squared euclidean distance, exact and good enough for ordering.
+fn distance_squared(a: &Coord, b: &Coord) -> i32 {
+    let d = *a - *b;
+    d.x.pow(2) + d.y.pow(2)
+}
 
-    if atan > 0.0 {
-        (2.0 * std::f32::consts::PI - atan)
-    } else {
-        atan * -1.0
+/// A direction from the station, reduced so collinear asteroids share it.
+/// Ordered clockwise, starting from straight up (`y` grows downwards).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct SweepDirection {
+    quadrant: u8,
+    dx: i32,
+    dy: i32,
+}
+
+impl SweepDirection {
+    fn new(dx: i32, dy: i32) -> Self {
+        let (dx, dy) = reduce_direction(dx, dy);
+
+        let quadrant = if dx >= 0 && dy < 0 {
+            0
+        } else if dx > 0 && dy >= 0 {
+            1
+        } else if dx <= 0 && dy > 0 {
+            2
+        } else {
+            3
+        };
+
+        SweepDirection { quadrant, dx, dy }
     }
 }
 
-/// How many different angles can we see from our asteroid?
+impl PartialOrd for SweepDirection {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SweepDirection {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Within a quadrant, the cross product tells which of the two comes first clockwise.
+        self.quadrant
+            .cmp(&other.quadrant)
+            .then_with(|| (other.dx * self.dy - other.dy * self.dx).cmp(&0))
+    }
+}
+
+/// How many different directions can we see from our asteroid?
+/// Only the closest asteroid in each direction is visible, so that's the number of visible asteroids.
 fn count_visible_asteroids(asteroids: &Vec<Coord>, asteroid: &Coord) -> usize {
-    let mut visible_count = HashSet::with_capacity(asteroids.len());
+    let mut directions = HashSet::with_capacity(asteroids.len());
 
     for another in asteroids.iter() {
         if asteroid == another {
             continue;
         }
 
-        let angle = (angle(asteroid, another) * 10000.0) as i32;
-        visible_count.insert(angle);
+        let d = *another - *asteroid;
+        directions.insert(reduce_direction(d.x, d.y));
     }
 
-    visible_count.len()
+    directions.len()
 }
 
+/// Ties are broken in favor of the smallest `(x, y)`, so the result doesn't depend on input order.
 fn best_asteroid(asteroids: &Vec<Coord>) -> Result<(Coord, usize)> {
     asteroids
         .iter()
         .map(|&astroid| (astroid, count_visible_asteroids(&asteroids, &astroid)))
-        .max_by_key(|(_v, visible_count)| visible_count.clone())
+        .max_by_key(|&(v, visible_count)| (visible_count, Reverse(v)))
         .context("Inconclusive maximum")
 }
 
-pub fn part_1(input: &str) -> Result<(Coord, usize)> {
+pub fn part_1(input: &str) -> Result<(Position, usize)> {
     let asteroids = parse_input(input);
 
     if asteroids.is_empty() {
         bail!("Input is empty.");
     }
 
-    best_asteroid(&asteroids)
+    let (best, visible_count) = best_asteroid(&asteroids)?;
+    Ok((best.into(), visible_count))
 }
 
 #[derive(Eq, Debug, Clone)]
@@ -110,67 +153,99 @@ impl Ord for Target {
     }
 }
 
-pub fn part_2(input: &str) -> Result<(Coord, usize)> {
-    let asteroids = parse_input(input);
-
-    if asteroids.is_empty() {
-        bail!("Input is empty.");
-    }
-
-    let (origin, _) = best_asteroid(&asteroids)?;
-
-    // {Angle -> (Minimum)BinaryHeap[Vertex sorted by distance]}.
-    let mut laser_queue = HashMap::new();
-
-    // Clone the len here since we move out of `asteroids`.
-    let total_asteroids = asteroids.len();
+/// Every asteroid except the station, in the order the laser vaporizes them.
+fn vaporization_order(asteroids: &[Coord], station: Coord) -> Vec<Coord> {
+    // {Direction -> (Minimum)BinaryHeap[Vertex sorted by distance]}.
+    let mut laser_queue = BTreeMap::new();
 
-    for asteroid in asteroids.into_iter() {
-        if asteroid == origin {
+    for &asteroid in asteroids.iter() {
+        if asteroid == station {
             continue;
         }
 
-        let angle_key = (angle_abs(&origin, &asteroid) * 10000.0).round() as i32;
-        let distance_key = (distance(&origin, &asteroid) * 10000.0).round() as i32;
-
+        let d = asteroid - station;
         let target = Target {
             coord: asteroid,
-            distance: distance_key,
+            distance: distance_squared(&station, &asteroid),
         };
 
         laser_queue
-            .entry(angle_key)
+            .entry(SweepDirection::new(d.x, d.y))
             .or_insert_with(BinaryHeap::new)
-            .push(std::cmp::Reverse(target));
+            .push(Reverse(target));
     }
 
-    let mut keys_iter = laser_queue.keys().cloned().sorted().cycle();
-    let mut number_of_astroids_destroyed = 0;
-    let mut last_destroyed = None;
+    let mut order = Vec::with_capacity(asteroids.len());
 
-    while (number_of_astroids_destroyed < 200) && (number_of_astroids_destroyed <= total_asteroids)
-    {
-        debug!("{} -> {:?}", number_of_astroids_destroyed, last_destroyed);
-        let next_angle = keys_iter.next().expect("This iterator is repeating");
+    // Every rotation destroys the closest asteroid in each direction, `retain` visits directions in order.
+    while !laser_queue.is_empty() {
+        laser_queue.retain(|direction, targets| {
+            if let Some(Reverse(target)) = targets.pop() {
+                debug!("Destroyed {:?} at {:?}", target.coord, direction);
+                order.push(target.coord);
+            }
+            !targets.is_empty()
+        });
+    }
 
-        debug!("Aligning at angle {}", next_angle);
-        debug!("Targets: {:?}", laser_queue.get(&next_angle));
+    order
+}
 
-        if let Some(ref mut astroids_in_angle) = laser_queue.get_mut(&next_angle) {
-            if let Some(std::cmp::Reverse(asteroid)) = astroids_in_angle.pop() {
-                number_of_astroids_destroyed += 1;
-                last_destroyed = Some(asteroid.coord)
-            }
-        }
+/// Finds the `nth` (starting from 1) asteroid vaporized by the laser, the puzzle asks for the 200th.
+pub fn part_2(input: &str, nth: usize) -> Result<(Position, usize)> {
+    let asteroids = parse_input(input);
+
+    if asteroids.is_empty() {
+        bail!("Input is empty.");
+    }
+
+    let (origin, _) = best_asteroid(&asteroids)?;
+
+    if nth == 0 {
+        bail!("Asteroids are counted from 1.");
     }
 
-    let last_result = last_destroyed.unwrap();
-    Ok((last_result, (last_result.0 * 100 + last_result.1) as usize))
+    let order = vaporization_order(&asteroids, origin);
+    let last_result = *order.get(nth - 1).with_context(|| {
+        format!(
+            "Only {} asteroids can be vaporized, cannot find asteroid number {}",
+            order.len(),
+            nth
+        )
+    })?;
+
+    Ok((
+        last_result.into(),
+        (last_result.x * 100 + last_result.y) as usize,
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    const LARGE_EXAMPLE: &str = "
+.#..##.###...#######
+##.############..##.
+.#.######.########.#
+.###.#######.####.#.
+#####.##.#.##.###.##
+..#####..#.#########
+####################
+#.####....###.#.#.##
+##.#################
+#####.##.###..####..
+..######..##.#######
+####.##.####...##..#
+.#####..#.######.###
+##...#.##########...
+#.##########.#######
+.####.#.###.###.#.##
+....##.##.###..#####
+.#.#.###########.###
+#.#.#.#####.####.###
+###.##.####.##.#..##
+";
     #[test]
     fn test_parse_input() {
         assert_eq!(
@@ -194,22 +269,70 @@ mod tests {
                 (3, 4),
                 (4, 4)
             ]
+            .into_iter()
+            .map(Coord::from)
+            .collect::<Vec<Coord>>()
         )
     }
 
     #[test]
-    fn test_angle() {
-        assert_eq!(angle((11, 13), (11, 17)), angle((11, 13), (11, 14)));
+    fn test_reduce_direction() {
+        assert_eq!(reduce_direction(2, 4), (1, 2));
+        assert_eq!(reduce_direction(-2, 0), (-1, 0));
+        assert_eq!(reduce_direction(0, -7), (0, -1));
+        assert_eq!(reduce_direction(-6, 9), (-2, 3));
+        assert_eq!(reduce_direction(3, -5), (3, -5));
+        assert_eq!(reduce_direction(0, 0), (0, 0));
     }
 
     #[test]
-    fn test_angle_2() {
-        assert_eq!(angle((11, 13), (11, 4)), 0.0);
+    fn test_sweep_direction_order() {
+        let clockwise = [
+            (0, -1),
+            (1, -3),
+            (1, -1),
+            (1, 0),
+            (2, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ];
+
+        for pair in clockwise.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert!(
+                SweepDirection::new(a.0, a.1) < SweepDirection::new(b.0, b.1),
+                "{:?} < {:?}",
+                a,
+                b
+            );
+        }
+
+        assert_eq!(SweepDirection::new(2, -6), SweepDirection::new(1, -3));
+    }
+
+    #[test]
+    fn test_vaporization_order() {
+        let asteroids = parse_input(LARGE_EXAMPLE);
+        let order = vaporization_order(&asteroids, Coord::new(11, 13));
+
+        assert_eq!(order.len(), asteroids.len() - 1);
+        assert_eq!(order[0], Coord::new(11, 12));
+        assert_eq!(order[1], Coord::new(12, 1));
+        assert_eq!(order[2], Coord::new(12, 2));
+        assert_eq!(order[199], Coord::new(8, 2));
+        assert_eq!(order[200], Coord::new(10, 9));
+        assert_eq!(order[298], Coord::new(11, 1));
     }
 
     #[test]
-    fn test_angle_3() {
-        assert!(angle((11, 13), (12, 1)) < angle((11, 13), (10, 1)));
+    fn test_count_visible_asteroids() {
+        // Seen from the origin, (2, 0) hides (4, 0) and (2, 2) hides (4, 4).
+        let asteroids = parse_input("#.#.#\n.....\n..#..\n.....\n....#");
+
+        assert_eq!(count_visible_asteroids(&asteroids, &Coord::new(0, 0)), 2);
+        assert_eq!(count_visible_asteroids(&asteroids, &Coord::new(2, 0)), 4);
     }
 
     #[test]
@@ -241,9 +364,8 @@ mod tests {
 ###.##.####.##.#..##
 "
             )
-            .unwrap()
-            .1,
-            210
+            .unwrap(),
+            ((11, 13), 210)
         )
     }
 
@@ -274,13 +396,44 @@ mod tests {
 .#.#.###########.###
 #.#.#.#####.####.###
 ###.##.####.##.#..##
-"
+",
+                200
             )
             .unwrap(),
             ((8, 2), 802)
         )
     }
 
+    #[test]
+    fn test_part2_nth() {
+        let input = "
+.#..#
+.....
+#####
+....#
+...##
+";
+        // The station is at (3, 4), straight above it is (3, 2).
+        assert_eq!(part_2(input, 1).unwrap(), ((3, 2), 302));
+        // (1, 0) hides behind (2, 2), so it is the only one left for the second rotation.
+        assert_eq!(part_2(input, 9).unwrap(), ((1, 0), 100));
+
+        assert!(part_2(input, 10).is_err());
+        assert!(part_2(input, 0).is_err());
+    }
+
+    #[test]
+    fn test_part1_tie_breaking() {
+        // Every asteroid sees all the others.
+        assert_eq!(part_1("#.#\n...\n#.#").unwrap(), ((0, 0), 3));
+        assert_eq!(part_1("#.#").unwrap(), ((0, 0), 1));
+
+        // The same asteroids, listed in a different order.
+        let mut asteroids = parse_input("#.#\n...\n#.#");
+        asteroids.reverse();
+        assert_eq!(best_asteroid(&asteroids).unwrap(), (Coord::new(0, 0), 3));
+    }
+
     #[test]
     fn test_part1_41() {
         env_logger::try_init().ok();
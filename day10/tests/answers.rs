@@ -0,0 +1,15 @@
+use day10::{part_1, part_2};
+
+#[test]
+fn test_puzzle_answers() {
+    let input = match aoc_io::try_read_input(10) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 10 is missing");
+            return;
+        }
+    };
+
+    assert_eq!(part_1(&input).unwrap(), ((25, 31), 329));
+    assert_eq!(part_2(&input, 200).unwrap(), ((5, 12), 512));
+}
@@ -2,6 +2,7 @@
 
 use anyhow::Error;
 use anyhow::{bail, Context, Result};
+use aoc_coord::Coord;
 use intcode_computer::{ExecutionStatus, IntcodeComputer};
 use log::debug;
 use std::collections::HashMap;
@@ -15,8 +16,20 @@ enum Direction {
     Right,
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Color {
+impl Direction {
+    /// Offset of a single step in this direction, "up" is towards positive `y`.
+    fn offset(self) -> Coord {
+        match self {
+            Direction::Up => Coord::new(0, 1),
+            Direction::Down => Coord::new(0, -1),
+            Direction::Left => Coord::new(-1, 0),
+            Direction::Right => Coord::new(1, 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
     Black,
     White,
 }
@@ -51,10 +64,8 @@ pub fn part_1(input: &str) -> Result<usize> {
     Ok(tiles.len())
 }
 
-type Coord = (i32, i32);
-
 fn tiles(robot: &mut IntcodeComputer, start_color: Color) -> Result<HashMap<Coord, Color>> {
-    let mut position = (0, 0);
+    let mut position = Coord::ORIGIN;
     let mut robot_direction = Direction::Up;
     let mut visited_tiles = HashMap::new();
     visited_tiles.insert(position, start_color);
@@ -62,7 +73,7 @@ fn tiles(robot: &mut IntcodeComputer, start_color: Color) -> Result<HashMap<Coor
     'outer: loop {
         let panel = visited_tiles.entry(position).or_insert(Color::Black);
         debug!("Panel {:?} is {:?}", position, panel);
-        robot.write_to_input(vec![panel.clone().into()])?;
+        robot.write_to_input([(*panel).into()])?;
 
         'inner: loop {
             match robot.step()? {
@@ -72,6 +83,13 @@ fn tiles(robot: &mut IntcodeComputer, start_color: Color) -> Result<HashMap<Coor
             }
         }
 
+        if robot.output_len() != 2 {
+            bail!(
+                "Expected robot to output a color and a direction, found {} values",
+                robot.output_len()
+            );
+        }
+
         let new_panel_color = Color::try_from(
             robot
                 .read_from_output()
@@ -100,36 +118,172 @@ fn tiles(robot: &mut IntcodeComputer, start_color: Color) -> Result<HashMap<Coor
             _ => bail!("Invalid turn `{}`", turn),
         };
 
-        position = match robot_direction {
-            Direction::Up => (position.0, position.1 + 1),
-            Direction::Down => (position.0, position.1 - 1),
-            Direction::Left => (position.0 - 1, position.1),
-            Direction::Right => (position.0 + 1, position.1),
-        };
+        position += robot_direction.offset();
     }
 
     Ok(visited_tiles)
 }
 
-pub fn part_2(input: &str) -> Result<()> {
+/// Draws the hull, `#` for white panels and `.` for everything else.
+/// The grid covers exactly the panels the robot visited, with positive `y` at the top.
+fn render_hull(tiles: &HashMap<Coord, Color>) -> String {
+    let min_x = tiles.keys().map(|c| c.x).min().unwrap_or(0);
+    let max_x = tiles.keys().map(|c| c.x).max().unwrap_or(0);
+    let min_y = tiles.keys().map(|c| c.y).min().unwrap_or(0);
+    let max_y = tiles.keys().map(|c| c.y).max().unwrap_or(0);
+
+    let mut hull = String::with_capacity(((max_x - min_x + 2) * (max_y - min_y + 1)) as usize);
+
+    for y in (min_y..=max_y).rev() {
+        for x in min_x..=max_x {
+            hull.push(match tiles.get(&Coord::new(x, y)) {
+                Some(Color::White) => '#',
+                Some(Color::Black) | None => '.',
+            });
+        }
+        hull.push('\n');
+    }
+
+    hull
+}
+
+const GLYPH_WIDTH: i32 = 4;
+const GLYPH_HEIGHT: i32 = 6;
+
+/// Letters of the AoC font, 4x6 pixels each, rows joined top to bottom.
+const GLYPHS: [(char, &str); 16] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+/// Reads the letters painted (in white) on the hull.
+/// Letters are 4 pixels wide with a blank column between them, unknown letters are read as `?`.
+pub fn read_hull_text(tiles: &HashMap<Coord, Color>) -> String {
+    let white: Vec<&Coord> = tiles
+        .iter()
+        .filter(|(_, &color)| color == Color::White)
+        .map(|(coord, _)| coord)
+        .collect();
+
+    let (min_x, max_x, max_y) = match (
+        white.iter().map(|c| c.x).min(),
+        white.iter().map(|c| c.x).max(),
+        white.iter().map(|c| c.y).max(),
+    ) {
+        (Some(min_x), Some(max_x), Some(max_y)) => (min_x, max_x, max_y),
+        _ => return String::new(),
+    };
+
+    let is_white = |x, y| tiles.get(&Coord::new(x, y)) == Some(&Color::White);
+
+    (min_x..=max_x)
+        .step_by(GLYPH_WIDTH as usize + 1)
+        .map(|left| {
+            let mut cell = String::with_capacity((GLYPH_WIDTH * GLYPH_HEIGHT) as usize);
+            for y in ((max_y - GLYPH_HEIGHT + 1)..=max_y).rev() {
+                for x in left..left + GLYPH_WIDTH {
+                    cell.push(if is_white(x, y) { '#' } else { '.' });
+                }
+            }
+
+            GLYPHS
+                .iter()
+                .find(|(_, glyph)| *glyph == cell)
+                .map_or('?', |&(letter, _)| letter)
+        })
+        .collect()
+}
+
+pub fn part_2(input: &str) -> Result<String> {
     let program = IntcodeComputer::parse_program(input)?;
     let mut robot = IntcodeComputer::new(program);
 
     let tiles = tiles(&mut robot, Color::White)?;
 
-    for i in -5..10 {
-        for j in -20..60 {
-            if j % 10 == 0 {
-                print!(" ")
-            }
-            match tiles.get(&(j, -i)) {
-                Some(Color::White) => print!("#"),
-                Some(Color::Black) => print!("."),
-                _ => print!("."),
+    Ok(render_hull(&tiles))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_hull() {
+        let mut tiles = HashMap::new();
+        tiles.insert(Coord::new(0, 0), Color::White);
+        tiles.insert(Coord::new(1, 0), Color::Black);
+        tiles.insert(Coord::new(1, -1), Color::White);
+        tiles.insert(Coord::new(-1, 1), Color::White);
+
+        assert_eq!(render_hull(&tiles), "#..\n.#.\n..#\n");
+        assert_eq!(render_hull(&HashMap::new()), ".\n");
+    }
+
+    #[test]
+    fn test_render_hull_far_from_origin() {
+        let mut tiles = HashMap::new();
+        tiles.insert(Coord::new(100, 30), Color::White);
+        tiles.insert(Coord::new(-40, -30), Color::White);
+
+        let hull = render_hull(&tiles);
+        let rows: Vec<&str> = hull.lines().collect();
+
+        assert_eq!(rows.len(), 61);
+        assert!(rows.iter().all(|row| row.len() == 141));
+        assert_eq!(rows[0].find('#'), Some(140));
+        assert_eq!(rows[60].find('#'), Some(0));
+        assert_eq!(hull.matches('#').count(), 2);
+    }
+
+    /// Paints `#` pixels white, top row first.
+    fn paint(rows: &[&str]) -> HashMap<Coord, Color> {
+        let mut tiles = HashMap::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let color = if c == '#' { Color::White } else { Color::Black };
+                tiles.insert(Coord::new(x as i32, -(y as i32)), color);
             }
         }
-        println!()
+        tiles
     }
 
-    Ok(())
+    #[test]
+    fn test_read_hull_text() {
+        let tiles = paint(&[
+            "..#..#.###..#..#.",
+            "..#..#.#..#.#..#.",
+            "..####.#..#.#..#.",
+            "..#..#.###..#..#.",
+            "..#..#.#.#..#..#.",
+            "..#..#.#..#..##..",
+        ]);
+        assert_eq!(read_hull_text(&tiles), "HRU");
+
+        let tiles = paint(&["#..#.#", ".##..#", "#..#.#", ".....#", ".....#", "......"]);
+        assert_eq!(read_hull_text(&tiles), "??");
+
+        assert_eq!(read_hull_text(&HashMap::new()), "");
+    }
+
+    #[test]
+    fn test_part_2_synthetic_robot() {
+        // Paints the starting panel white and turns right, then black and turns left.
+        // Finally it reads the color of the panel above and halts.
+        let program = "3,100,104,1,104,1,3,100,104,0,104,0,3,100,99";
+        assert_eq!(part_2(program).unwrap(), "..\n#.\n");
+    }
 }
@@ -0,0 +1,14 @@
+use day11::part_1;
+
+#[test]
+fn test_puzzle_answers() {
+    let input = match aoc_io::try_read_input(11) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 11 is missing");
+            return;
+        }
+    };
+
+    assert_eq!(part_1(&input).unwrap(), 2041);
+}
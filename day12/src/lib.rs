@@ -9,10 +9,10 @@ use std::str::FromStr;
 use std::{fmt, ops};
 
 #[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
-struct Point3 {
-    x: i32,
-    y: i32,
-    z: i32,
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
 }
 
 impl fmt::Display for Point3 {
@@ -35,12 +35,20 @@ impl ops::AddAssign<&Point3> for Point3 {
 }
 
 #[derive(Debug, PartialOrd, PartialEq, Clone)]
-struct Moon {
+pub struct Moon {
     position: Point3,
     velocity: Point3,
 }
 
 impl Moon {
+    pub fn position(&self) -> Point3 {
+        self.position
+    }
+
+    pub fn velocity(&self) -> Point3 {
+        self.velocity
+    }
+
     pub fn apply_gravity(&mut self, other: &Moon) {
         match self.position.x.cmp(&other.position.x) {
             Ordering::Less => self.velocity.x += 1,
@@ -64,7 +72,7 @@ impl Moon {
         self.position += &self.velocity
     }
 
-    pub fn potential_energy(&self) -> i32 {
+    pub fn potential_energy(&self) -> i64 {
         let potential_energy =
             self.position.x.abs() + self.position.y.abs() + self.position.z.abs();
         let kinetic_energy = self.velocity.x.abs() + self.velocity.y.abs() + self.velocity.z.abs();
@@ -87,10 +95,10 @@ impl FromStr for Moon {
                 Regex::new(r#"\w=(?P<position>-?\d+),?\s?"#).expect("A valid regex");
         }
 
-        let coords: Vec<i32> = MOON_RE
+        let coords: Vec<i64> = MOON_RE
             .captures_iter(s)
             .filter_map(|c| c.name("position"))
-            .map(|p| p.as_str().parse::<i32>().expect("Matched a number"))
+            .map(|p| p.as_str().parse::<i64>().expect("Matched a number"))
             .collect();
 
         if coords.len() != 3 {
@@ -149,23 +157,79 @@ impl Simulation {
         }
     }
 
-    pub fn total_potential_energy(&self) -> i32 {
+    pub fn total_potential_energy(&self) -> i64 {
         self.0.iter().map(|moon| moon.potential_energy()).sum()
     }
+
+    /// Positions and velocities of all moons along a single axis.
+    fn axis_state(&self, axis: fn(&Point3) -> i64) -> Vec<(i64, i64)> {
+        self.0
+            .iter()
+            .map(|moon| (axis(&moon.position), axis(&moon.velocity)))
+            .collect()
+    }
 }
 
-pub fn part_1(input: &str) -> Result<i32> {
-    let mut moons = Simulation::from_str(input)?;
+/// Yields the state of the moons after every step.
+impl Iterator for Simulation {
+    type Item = Vec<Moon>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.step();
+        Some(self.0.clone())
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
+/// Total energy in the system after simulating `steps` steps.
+pub fn total_energy_after(input: &str, steps: usize) -> Result<i64> {
+    let mut simulation = Simulation::from_str(input)?;
 
-    for _ in 0..1000 {
-        moons.step()
+    for _ in 0..steps {
+        simulation.step();
     }
 
-    Ok(moons.total_potential_energy())
+    Ok(simulation.total_potential_energy())
 }
 
-pub fn part_2(input: &str) -> Result<i32> {
-    Ok(0)
+pub fn part_1(input: &str) -> Result<i64> {
+    total_energy_after(input, 1000)
+}
+
+/// Number of steps until the moons return to a previous state.
+/// The axes don't affect each other, so each one has its own period and the system repeats once they all do.
+/// Every state has exactly one previous state, so the first state to repeat is the initial one.
+pub fn part_2(input: &str) -> Result<u64> {
+    let mut moons = Simulation::from_str(input)?;
+
+    let axes: [fn(&Point3) -> i64; 3] = [|p| p.x, |p| p.y, |p| p.z];
+    let initial: Vec<Vec<(i64, i64)>> = axes.iter().map(|&a| moons.axis_state(a)).collect();
+    let mut periods: [Option<u64>; 3] = [None; 3];
+
+    let mut steps = 0;
+    while periods.iter().any(Option::is_none) {
+        moons.step();
+        steps += 1;
+
+        for (i, &axis) in axes.iter().enumerate() {
+            if periods[i].is_none() && moons.axis_state(axis) == initial[i] {
+                periods[i] = Some(steps);
+            }
+        }
+    }
+
+    Ok(periods.iter().flatten().fold(1, |acc, &p| lcm(acc, p)))
 }
 
 #[cfg(test)]
@@ -203,4 +267,90 @@ mod test {
         }
         assert_eq!(simulation.total_potential_energy(), 179)
     }
+
+    #[test]
+    fn test_simulation_iterator() {
+        let mut simulation = Simulation::from_str(
+            "<x=-1, y=0, z=2>
+<x=2, y=-10, z=-7>
+<x=4, y=-8, z=8>
+<x=3, y=5, z=-1>
+",
+        )
+        .unwrap();
+
+        let p = |x, y, z| Point3 { x, y, z };
+        let state: Vec<(Point3, Point3)> = simulation
+            .next()
+            .unwrap()
+            .iter()
+            .map(|moon| (moon.position(), moon.velocity()))
+            .collect();
+
+        assert_eq!(
+            state,
+            vec![
+                (p(2, -1, 1), p(3, -1, -1)),
+                (p(3, -7, -4), p(1, 3, 3)),
+                (p(1, -7, 5), p(-3, 1, -3)),
+                (p(2, 2, 0), p(-1, -3, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_total_energy_after() {
+        let input = "<x=-1, y=0, z=2>
+<x=2, y=-10, z=-7>
+<x=4, y=-8, z=8>
+<x=3, y=5, z=-1>
+";
+        // Nothing moves yet, so there is no kinetic energy.
+        assert_eq!(total_energy_after(input, 0).unwrap(), 0);
+        assert_eq!(total_energy_after(input, 10).unwrap(), 179);
+
+        let input = "<x=-8, y=-10, z=0>
+<x=5, y=5, z=10>
+<x=2, y=-7, z=3>
+<x=9, y=-8, z=-3>
+";
+        assert_eq!(total_energy_after(input, 100).unwrap(), 1940);
+    }
+
+    #[test]
+    fn test_long_simulation() {
+        let input = "<x=-8, y=-10, z=0>
+<x=5, y=5, z=10>
+<x=2, y=-7, z=3>
+<x=9, y=-8, z=-3>
+";
+        // Would panic in debug builds if anything overflowed.
+        assert!(total_energy_after(input, 200_000).unwrap() > 0);
+    }
+
+    #[test]
+    fn test_display() {
+        let moon = Moon::from_str("<x=-1, y=0, z=2>").unwrap();
+        assert_eq!(
+            moon.to_string(),
+            "pos=<x= -1, y=  0, z=  2>, vel=<x=  0, y=  0, z=  0>"
+        );
+    }
+
+    #[test]
+    fn test_part_2() {
+        let input = "<x=-1, y=0, z=2>
+<x=2, y=-10, z=-7>
+<x=4, y=-8, z=8>
+<x=3, y=5, z=-1>
+";
+        assert_eq!(part_2(input).unwrap(), 2772);
+
+        let input = "<x=-8, y=-10, z=0>
+<x=5, y=5, z=10>
+<x=2, y=-7, z=3>
+<x=9, y=-8, z=-3>
+";
+        assert_eq!(part_2(input).unwrap(), 4686774924);
+    }
 }
@@ -0,0 +1,15 @@
+use day12::{part_1, part_2};
+
+#[test]
+fn test_puzzle_answers() {
+    let input = match aoc_io::try_read_input(12) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 12 is missing");
+            return;
+        }
+    };
+
+    assert_eq!(part_1(&input).unwrap(), 12082);
+    assert_eq!(part_2(&input).unwrap(), 295693702908636);
+}
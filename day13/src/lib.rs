@@ -1,14 +1,20 @@
 #![deny(unused_must_use)]
 
 use anyhow::{bail, Context, Result};
+use aoc_coord::Coord;
 use console::Term;
 use intcode_computer::{ExecutionStatus, IntcodeComputer};
-use std::cmp;
-use std::convert::TryFrom;
+use itertools::Itertools;
 use std::iter::FromIterator;
 use std::thread;
 use std::time::Duration;
 
+mod strategy;
+
+pub use strategy::{FollowBall, PaddleStrategy, Speedrun};
+
+type Grid = Vec<Vec<char>>;
+
 #[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
 enum Tile {
     // empty tile. No game object appears in this tile
@@ -48,63 +54,128 @@ pub fn part_1(input: &str) -> Result<usize> {
     let mut tiles = vec![];
     for tile in output.chunks_exact(3) {
         let (x, y, t) = (tile[0], tile[1], tile[2]);
-        tiles.push(((x, y), Tile::try_from(t)?));
+        tiles.push((Coord::new(x as i32, y as i32), Tile::from(t)));
     }
 
     Ok(tiles.iter().filter(|(_, t)| *t == Tile::Block).count())
 }
 
-pub fn part_2(input: &str, interactive: bool) -> Result<i64> {
+/// Renders a captured frame of the game grid, one line per row.
+pub fn frame_to_string(grid: &[Vec<char>]) -> String {
+    grid.iter()
+        .map(|row| String::from_iter(row.iter()))
+        .join("\n")
+}
+
+/// A single screen drawn by the game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub grid: Grid,
+    pub score: i64,
+}
+
+/// How a game ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameOutcome {
+    pub score: i64,
+    pub blocks_left: usize,
+    pub grid: Grid,
+}
+
+/// Plays the game headlessly using `strategy`, until all blocks are broken.
+pub fn play(input: &str, strategy: &mut dyn PaddleStrategy) -> Result<GameOutcome> {
+    play_with_frames(input, strategy, |_| Ok(()))
+}
+
+/// Plays the game headlessly and returns the grid as it looks after `frame` frames.
+/// Frame `0` is the screen drawn before the joystick is first moved.
+pub fn capture_frame(input: &str, frame: usize) -> Result<Grid> {
+    let last = run(input, &mut FollowBall, Some(frame), &mut |_| Ok(()))?;
+    Ok(last.grid)
+}
+
+/// Plays the game with `strategy` controlling the paddle, `FollowBall` is enough to win.
+pub fn part_2(input: &str, interactive: bool, strategy: &mut impl PaddleStrategy) -> Result<i64> {
+    let last = if interactive {
+        let term = Term::stdout();
+        assert!(term.is_term());
+        play_with_frames(input, strategy, |frame| draw(&term, frame))?
+    } else {
+        play(input, strategy)?
+    };
+
+    Ok(last.score)
+}
+
+/// Plays the game using `strategy`, handing every frame to `on_frame` as soon as it is drawn.
+pub fn play_with_frames(
+    input: &str,
+    strategy: &mut dyn PaddleStrategy,
+    mut on_frame: impl FnMut(&Frame) -> Result<()>,
+) -> Result<GameOutcome> {
+    run(input, strategy, None, &mut on_frame)
+}
+
+/// Draws a frame on the terminal.
+fn draw(term: &Term, frame: &Frame) -> Result<()> {
+    term.clear_screen()?;
+    term.write_line(&format!("SCORE: {}", frame.score))?;
+    term.write_line(&frame_to_string(&frame.grid))?;
+
+    thread::sleep(Duration::from_millis(10));
+
+    Ok(())
+}
+
+/// Strategies get positions in the same `i64`s the game outputs.
+fn position(c: Coord) -> (i64, i64) {
+    (c.x.into(), c.y.into())
+}
+
+/// Runs the game loop, stopping once all blocks are broken or `frame_limit` frames have been drawn.
+/// Returns how the game ended.
+fn run(
+    input: &str,
+    strategy: &mut dyn PaddleStrategy,
+    frame_limit: Option<usize>,
+    on_frame: &mut dyn FnMut(&Frame) -> Result<()>,
+) -> Result<GameOutcome> {
     let program = IntcodeComputer::parse_program(&input)?;
     let mut game = IntcodeComputer::new(program);
-    let mut score = 0;
     // PLAY FOR FREE
     game.set_addr(0, 2)?;
 
-    let mut grid = vec![vec![' '; 50]; 40];
-
-    let out = if interactive {
-        let t = Term::stdout();
-        assert!(t.is_term());
-        Some(t)
-    } else {
-        None
+    let mut current = Frame {
+        grid: vec![vec![' '; 50]; 40],
+        score: 0,
     };
 
-    let mut last_puck_position = (0, 0);
-    let mut last_ball_position = (0, 0);
+    let mut last_puck_position = Coord::ORIGIN;
+    let mut last_ball_position = Coord::ORIGIN;
     let mut blocks_left = 0;
     let mut input = [0_i64; 1];
     let mut output = Vec::with_capacity(100);
+    let mut frame = 0;
 
     'outer: loop {
-        input[0] = match last_puck_position.0.cmp(&last_ball_position.0) {
-            cmp::Ordering::Greater => -1,
-            cmp::Ordering::Less => 1,
-            cmp::Ordering::Equal => 0,
-        };
-
-        if interactive {
-            out.as_ref()
-                .expect("Terminal exists when interactive")
-                .clear_screen()?;
-            out.as_ref()
-                .expect("Terminal exists when interactive")
-                .write_line(&format!("SCORE: {}", score))?;
-        }
+        input[0] = strategy.next_move(
+            position(last_ball_position),
+            position(last_puck_position),
+            &current.grid,
+        );
 
-        game.write_to_input(&input)
+        game.write_to_input(input)
             .context("Failed to write to input")
             .unwrap();
 
-        'inner: loop {
+        let halted = 'inner: loop {
             let status = game.step().unwrap();
             match status {
-                ExecutionStatus::NeedInput => break 'inner,
-                ExecutionStatus::Halted => break 'inner,
+                ExecutionStatus::NeedInput => break 'inner false,
+                ExecutionStatus::Halted => break 'inner true,
                 ExecutionStatus::Done => {}
             }
-        }
+        };
 
         while let Ok(i) = game.read_from_output() {
             output.push(i)
@@ -114,22 +185,24 @@ pub fn part_2(input: &str, interactive: bool) -> Result<i64> {
 
         for tile in tiles_reader.by_ref() {
             let (x, y, t) = (tile[0], tile[1], Tile::from(tile[2]));
+            let position = Coord::new(x as i32, y as i32);
+            let grid = &mut current.grid;
 
             match (x, y, t) {
-                (-1, 0, Tile::SegmentDisplay(s)) => score = s,
+                (-1, 0, Tile::SegmentDisplay(s)) => current.score = s,
                 (-1, 0, _) => {}
                 (x, y, Tile::Empty) if grid[y as usize][x as usize] == '*' => {
                     blocks_left -= 1;
                 }
-                (x, y, Tile::Block) => blocks_left += 1,
-                (x, y, Tile::Ball) => {
-                    last_ball_position = (x, y);
+                (_, _, Tile::Block) => blocks_left += 1,
+                (_, _, Tile::Ball) => {
+                    last_ball_position = position;
                 }
-                (x, y, Tile::HorizontalPaddle) => {
-                    if last_ball_position == (x, y) {
+                (_, _, Tile::HorizontalPaddle) => {
+                    if last_ball_position == position {
                         bail!("Game over");
                     }
-                    last_puck_position = (x, y);
+                    last_puck_position = position;
                 }
                 _ => {}
             }
@@ -153,21 +226,226 @@ pub fn part_2(input: &str, interactive: bool) -> Result<i64> {
 
         output.truncate(0);
 
-        if blocks_left == 0 {
+        on_frame(&current)?;
+
+        if blocks_left == 0 || frame_limit == Some(frame) {
             break 'outer;
         }
 
-        if interactive {
-            for line in grid.iter() {
-                let line = String::from_iter(line.iter());
-                out.as_ref()
-                    .expect("Terminal exists when interactive")
-                    .write_line(&line)?;
+        if halted {
+            bail!("Game over, {} blocks left", blocks_left);
+        }
+
+        frame += 1;
+    }
+
+    Ok(GameOutcome {
+        score: current.score,
+        blocks_left: blocks_left as usize,
+        grid: current.grid,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_initial_frame() {
+        let frame = capture_frame(include_str!("../input/opcodes"), 0).unwrap();
+        let rendered = frame_to_string(&frame);
+
+        assert_eq!(rendered.lines().count(), frame.len());
+        assert!(rendered.contains('^'));
+        assert!(rendered.contains('*'));
+    }
+
+    /// Plays a fixed sequence of moves, then stays put. Remembers where the paddle was on every call.
+    struct Scripted {
+        moves: Vec<i64>,
+        paddle_positions: Vec<i64>,
+    }
+
+    impl PaddleStrategy for Scripted {
+        fn next_move(&mut self, _ball: (i64, i64), paddle: (i64, i64), _grid: &[Vec<char>]) -> i64 {
+            let next = self
+                .moves
+                .get(self.paddle_positions.len())
+                .copied()
+                .unwrap_or(0);
+            self.paddle_positions.push(paddle.0);
+            next
+        }
+    }
+
+    #[test]
+    fn test_scripted_strategy() {
+        let input = include_str!("../input/opcodes");
+        let moves = vec![-1, 0, 1, 1];
+        let mut strategy = Scripted {
+            moves: moves.clone(),
+            paddle_positions: vec![],
+        };
+
+        // Standing still once the script runs out loses the game.
+        let err = part_2(input, false, &mut strategy).unwrap_err();
+        assert!(err.to_string().starts_with("Game over"));
+
+        // The first move is chosen before anything is drawn, so the paddle's starting column
+        // comes from the initial screen instead.
+        let initial = capture_frame(input, 0).unwrap();
+        let start = initial
+            .iter()
+            .find_map(|row| row.iter().position(|&c| c == '_'));
+
+        let mut positions = strategy.paddle_positions;
+        assert!(positions.len() > moves.len());
+        positions[0] = start.unwrap() as i64;
+
+        // Every move shows up as the paddle position on the following call.
+        for (i, &m) in moves.iter().enumerate() {
+            assert_eq!(positions[i + 1] - positions[i], m, "move {}", i);
+        }
+    }
+
+    #[test]
+    fn test_play_with_frames() {
+        let mut scores = vec![];
+        let last = play_with_frames(include_str!("../input/opcodes"), &mut FollowBall, |frame| {
+            scores.push(frame.score);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(last.score, 21415);
+        assert_eq!(scores.last(), Some(&last.score));
+        assert!(scores.windows(2).all(|w| w[0] <= w[1]));
+        assert!(!frame_to_string(&last.grid).contains('*'));
+    }
+
+    #[test]
+    fn test_play_clears_the_board() {
+        let outcome = play(include_str!("../input/opcodes"), &mut FollowBall).unwrap();
+
+        assert_eq!(outcome.score, 21415);
+        assert_eq!(outcome.blocks_left, 0);
+        assert!(outcome.grid.iter().flatten().all(|&c| c != '*'));
+    }
+
+    /// A tiny breakout following the same rules as the game, so strategies can be played on a
+    /// small board. The ball starts moving up and to the right.
+    /// Returns how many blocks were left, and the number of frames it took.
+    fn play_board(board: &str, strategy: &mut dyn PaddleStrategy) -> (usize, usize) {
+        let mut grid: Grid = board.lines().map(|row| row.chars().collect()).collect();
+        let find = |grid: &Grid, tile: char| {
+            let y = grid.iter().position(|row| row.contains(&tile)).unwrap();
+            let x = grid[y].iter().position(|&c| c == tile).unwrap();
+            Coord::new(x as i32, y as i32)
+        };
+        let (mut ball, mut paddle) = (find(&grid, '@'), find(&grid, '_'));
+        let mut velocity = Coord::new(1, -1);
+
+        fn tile(grid: &mut Grid, c: Coord) -> &mut char {
+            &mut grid[c.y as usize][c.x as usize]
+        }
+
+        // Whether the ball bounces off `c`, breaking it if it's a block.
+        fn bounce(grid: &mut Grid, c: Coord) -> bool {
+            match *tile(grid, c) {
+                '*' => {
+                    *tile(grid, c) = ' ';
+                    true
+                }
+                '^' | '_' => true,
+                _ => false,
+            }
+        }
+
+        for frame in 0..1000 {
+            let blocks_left = grid.iter().flatten().filter(|&&c| c == '*').count();
+            if blocks_left == 0 || ball.y >= paddle.y {
+                return (blocks_left, frame);
+            }
+
+            let joystick = strategy.next_move(position(ball), position(paddle), &grid);
+            let moved = Coord::new(paddle.x + joystick as i32, paddle.y);
+            if *tile(&mut grid, moved) == ' ' {
+                *tile(&mut grid, paddle) = ' ';
+                *tile(&mut grid, moved) = '_';
+                paddle = moved;
+            }
+
+            // Bounce off whatever is next to the ball, otherwise move diagonally.
+            let horizontal = bounce(&mut grid, Coord::new(ball.x + velocity.x, ball.y));
+            let vertical = bounce(&mut grid, Coord::new(ball.x, ball.y + velocity.y));
+            if horizontal {
+                velocity.x = -velocity.x;
+            }
+            if vertical {
+                velocity.y = -velocity.y;
             }
 
-            thread::sleep(Duration::from_millis(10));
+            if !horizontal && !vertical {
+                if bounce(&mut grid, ball + velocity) {
+                    velocity = -velocity;
+                } else {
+                    *tile(&mut grid, ball) = ' ';
+                    ball += velocity;
+                    *tile(&mut grid, ball) = '@';
+                }
+            }
         }
+
+        panic!("The game never ended");
+    }
+
+    #[test]
+    fn test_speedrun_clears_small_board() {
+        let board = "\
+^^^^^^^^^
+^       ^
+^ ***** ^
+^       ^
+^       ^
+^   @   ^
+^       ^
+^   _   ^";
+
+        let (follow_left, follow_frames) = play_board(board, &mut FollowBall);
+        let (speedrun_left, speedrun_frames) = play_board(board, &mut Speedrun::default());
+
+        assert_eq!(follow_left, 0);
+        assert_eq!(speedrun_left, 0);
+        assert!(
+            speedrun_frames <= follow_frames,
+            "{} <= {}",
+            speedrun_frames,
+            follow_frames
+        );
     }
 
-    Ok(score)
+    #[test]
+    fn test_speedrun_moves_to_landing_column() {
+        let grid: Grid = "\
+^^^^^^^
+^     ^
+^     ^
+^     ^
+^     ^
+^     ^
+^  _  ^"
+            .lines()
+            .map(|row| row.chars().collect())
+            .collect();
+
+        // The ball is heading left, bounces off the wall and lands on column 2,
+        // while the paddle is between it and the landing column.
+        let (ball, paddle) = ((4, 1), (3, 6));
+        assert_eq!(FollowBall.next_move(ball, paddle, &grid), 1);
+
+        // Speedrun needs to see the ball twice to know where it's heading.
+        let mut speedrun = Speedrun::default();
+        speedrun.next_move((5, 0), paddle, &grid);
+        assert_eq!(speedrun.next_move(ball, paddle, &grid), -1);
+    }
 }
@@ -1,20 +1,20 @@
-use anyhow::{Context, Result};
-use day13::{part_1, part_2};
-use std::io::{stdin, Read};
+use anyhow::Result;
+use aoc_io::read_stdin;
+use day13::{part_1, part_2, FollowBall};
 use std::time::Instant;
 
 fn main() -> Result<()> {
     env_logger::init();
 
-    let mut input = String::new();
-
-    stdin()
-        .read_to_string(&mut input)
-        .context("Failed to read input from stdin")?;
+    let input = read_stdin()?;
 
     println!("Part 1 - {}", part_1(&input)?);
     let now = Instant::now();
-    println!("Part 2: {} in {:?}", part_2(&input, false)?, now.elapsed());
+    println!(
+        "Part 2: {} in {:?}",
+        part_2(&input, false, &mut FollowBall)?,
+        now.elapsed()
+    );
 
     Ok(())
 }
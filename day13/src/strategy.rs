@@ -0,0 +1,133 @@
+use aoc_coord::Coord;
+use std::cmp;
+
+/// Controls the paddle.
+pub trait PaddleStrategy {
+    /// Joystick position for the next frame, -1 is left, 0 is neutral and 1 is right.
+    /// Positions are `(x, y)`, and `grid` is the screen as drawn so far.
+    fn next_move(&mut self, ball: (i64, i64), paddle: (i64, i64), grid: &[Vec<char>]) -> i64;
+}
+
+fn move_towards(paddle: i64, target: i64) -> i64 {
+    match paddle.cmp(&target) {
+        cmp::Ordering::Greater => -1,
+        cmp::Ordering::Less => 1,
+        cmp::Ordering::Equal => 0,
+    }
+}
+
+/// Keeps the paddle right under the ball.
+#[derive(Debug, Default)]
+pub struct FollowBall;
+
+impl PaddleStrategy for FollowBall {
+    fn next_move(&mut self, ball: (i64, i64), paddle: (i64, i64), _grid: &[Vec<char>]) -> i64 {
+        move_towards(paddle.0, ball.0)
+    }
+}
+
+/// Predicts where the ball is going to land by simulating its trajectory against the current grid,
+/// and moves the paddle there directly.
+///
+/// Note that this does not finish the game in fewer frames than `FollowBall`: as long as the paddle
+/// catches the ball it doesn't change the ball's trajectory, so every winning strategy takes the
+/// same number of frames. It only gets the paddle into place earlier, instead of chasing the ball.
+#[derive(Debug, Default)]
+pub struct Speedrun {
+    /// Position of the ball on the previous move, used to derive its velocity.
+    previous_ball: Option<Coord>,
+}
+
+impl Speedrun {
+    fn is_solid(grid: &[Vec<char>], c: Coord) -> bool {
+        // Row and column 0 are walls, but are not drawn on the grid.
+        if c.x <= 0 || c.y <= 0 {
+            return true;
+        }
+
+        grid.get(c.y as usize)
+            .and_then(|row| row.get(c.x as usize))
+            .is_none_or(|&tile| tile == '^' || tile == '*')
+    }
+
+    /// Column the ball will be at when it reaches the row right above the paddle.
+    fn predict_landing(
+        grid: &[Vec<char>],
+        mut ball: Coord,
+        previous_ball: Coord,
+        paddle: Coord,
+    ) -> Option<i32> {
+        let mut velocity = ball - previous_ball;
+        if velocity.x.abs() != 1 || velocity.y.abs() != 1 {
+            return None;
+        }
+
+        // Blocks break when hit, so work on a copy.
+        let mut grid = grid.to_vec();
+        let max_steps = grid.len() * grid.first().map_or(0, Vec::len) * 2;
+
+        for _ in 0..max_steps {
+            if ball.y == paddle.y - 1 && velocity.y > 0 {
+                return Some(ball.x);
+            }
+
+            let horizontal = Coord::new(ball.x + velocity.x, ball.y);
+            let vertical = Coord::new(ball.x, ball.y + velocity.y);
+            let diagonal = ball + velocity;
+
+            let mut bounced = false;
+            for (hit, flip_x, flip_y) in [(horizontal, true, false), (vertical, false, true)] {
+                if Self::is_solid(&grid, hit) {
+                    Self::break_block(&mut grid, hit);
+                    if flip_x {
+                        velocity.x = -velocity.x;
+                    }
+                    if flip_y {
+                        velocity.y = -velocity.y;
+                    }
+                    bounced = true;
+                }
+            }
+
+            if !bounced && Self::is_solid(&grid, diagonal) {
+                Self::break_block(&mut grid, diagonal);
+                velocity = -velocity;
+                bounced = true;
+            }
+
+            if !bounced {
+                ball = diagonal;
+            }
+        }
+
+        None
+    }
+
+    fn break_block(grid: &mut [Vec<char>], c: Coord) {
+        if let Some(tile) = grid
+            .get_mut(c.y as usize)
+            .and_then(|row| row.get_mut(c.x as usize))
+        {
+            if *tile == '*' {
+                *tile = ' ';
+            }
+        }
+    }
+}
+
+impl PaddleStrategy for Speedrun {
+    fn next_move(&mut self, ball: (i64, i64), paddle: (i64, i64), grid: &[Vec<char>]) -> i64 {
+        let (ball, paddle) = (to_coord(ball), to_coord(paddle));
+        let previous_ball = self.previous_ball.replace(ball);
+
+        let target = previous_ball
+            .and_then(|previous_ball| Self::predict_landing(grid, ball, previous_ball, paddle))
+            .unwrap_or(ball.x);
+
+        move_towards(paddle.x as i64, target as i64)
+    }
+}
+
+fn to_coord((x, y): (i64, i64)) -> Coord {
+    Coord::new(x as i32, y as i32)
+}
@@ -0,0 +1,15 @@
+use day13::{part_1, part_2, FollowBall};
+
+#[test]
+fn test_puzzle_answers() {
+    let input = match aoc_io::try_read_input(13) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 13 is missing");
+            return;
+        }
+    };
+
+    assert_eq!(part_1(&input).unwrap(), 452);
+    assert_eq!(part_2(&input, false, &mut FollowBall).unwrap(), 21415);
+}
@@ -1,6 +1,7 @@
 #![deny(unused_must_use)]
 
-use anyhow::{bail, Context, Result};
+use anyhow::{bail, Context, Error, Result};
+use aoc_graph::Graph;
 use intcode_computer::{ExecutionStatus, IntcodeComputer};
 use log::debug;
 use petgraph::prelude::*;
@@ -15,11 +16,11 @@ use std::time::Duration;
 
 const ONE_TRILLION: usize = 1_000_000_000_000;
 
-type Chemical = (String, usize);
-type Reaction = (Chemical, Vec<Chemical>);
-type ReactionsMap = HashMap<String, Reaction>;
+pub type Chemical = (String, usize);
+pub type Reaction = (Chemical, Vec<Chemical>);
+pub type ReactionsMap = HashMap<String, Reaction>;
 
-fn parse_input(input: &str) -> Result<ReactionsMap> {
+pub fn parse_input(input: &str) -> Result<ReactionsMap> {
     let mut reactions = vec![];
     let reg = Regex::new(r#"(\d+) (\w+),?"#).unwrap();
 
@@ -50,14 +51,97 @@ fn parse_input(input: &str) -> Result<ReactionsMap> {
     Ok(reactions_map)
 }
 
+/// A reaction, with its ingredients referring to chemicals by their index in the `ProductionPlan`.
+struct Step {
+    produced: usize,
+    ingredients: Vec<(usize, usize)>,
+}
+
+/// The reactions in topological order (every chemical before the chemicals it is made of),
+/// so the ore needed for any amount of fuel can be computed in a single pass.
+struct ProductionPlan {
+    /// The reaction producing each chemical, `None` for ore.
+    steps: Vec<Option<Step>>,
+    fuel: usize,
+    ore: usize,
+}
+
+impl ProductionPlan {
+    fn new(reactions: &ReactionsMap) -> Result<Self> {
+        let mut graph = Graph::new();
+        for (product, (_, ingredients)) in reactions.iter() {
+            for (ingredient, _) in ingredients {
+                graph.add_edge(product.as_str(), ingredient.as_str());
+            }
+        }
+
+        let order = graph
+            .topological_sort()
+            .map_err(|e| Error::msg(e.to_string()))?;
+        let index: HashMap<&str, usize> = order.iter().enumerate().map(|(i, &c)| (c, i)).collect();
+
+        let steps = order
+            .iter()
+            .map(|&chemical| match reactions.get(chemical) {
+                Some(((_, produced), ingredients)) => Ok(Some(Step {
+                    produced: *produced,
+                    ingredients: ingredients
+                        .iter()
+                        .map(|(ingredient, quantity)| (index[ingredient.as_str()], *quantity))
+                        .collect(),
+                })),
+                None if chemical == "ORE" => Ok(None),
+                None => bail!("No reaction produces `{}`", chemical),
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(ProductionPlan {
+            steps,
+            fuel: *index.get("FUEL").context("No reaction produces `FUEL`")?,
+            ore: *index.get("ORE").context("No reaction uses `ORE`")?,
+        })
+    }
+
+    fn ore_needed(&self, fuel: usize) -> usize {
+        let mut needed = vec![0; self.steps.len()];
+        needed[self.fuel] = fuel;
+
+        // By the time we get to a chemical, everything that uses it has already been added to `needed`.
+        for (i, step) in self.steps.iter().enumerate() {
+            if let Some(step) = step {
+                let reactions = needed[i].div_ceil(step.produced);
+                for &(ingredient, quantity) in &step.ingredients {
+                    needed[ingredient] += quantity * reactions;
+                }
+            }
+        }
+
+        needed[self.ore]
+    }
+}
+
 pub fn part_1(input: &str) -> Result<usize> {
     let reactions = parse_input(input)?;
-    Ok(computer_ore_needed_for_fuel(1, &reactions))
+    Ok(ProductionPlan::new(&reactions)?.ore_needed(1))
 }
 
-/// This is basically a modified DFS with some state.
-fn computer_ore_needed_for_fuel(how_much: usize, reactions: &ReactionsMap) -> usize {
+/// Produces `count` batches of `batch` fuel one after the other,
+/// leftovers from each batch are used by the following batches.
+pub fn ore_for_fuel_batches(batch: usize, count: usize, reactions: &ReactionsMap) -> usize {
     let mut have = HashMap::new();
+
+    (0..count)
+        .map(|_| ore_needed_with_leftovers(batch, reactions, &mut have))
+        .sum()
+}
+
+/// Ore needed to produce `how_much` fuel, starting with (and updating) the chemicals in `have`.
+/// This is basically a modified DFS with some state.
+fn ore_needed_with_leftovers<'a>(
+    how_much: usize,
+    reactions: &'a ReactionsMap,
+    have: &mut HashMap<&'a str, usize>,
+) -> usize {
     let mut needed = Vec::new();
     let mut total_ore = 0;
 
@@ -89,15 +173,16 @@ fn computer_ore_needed_for_fuel(how_much: usize, reactions: &ReactionsMap) -> us
 /// bisect to find correct amount..
 pub fn part_2(input: &str) -> Result<usize> {
     let reactions = parse_input(input)?;
+    let plan = ProductionPlan::new(&reactions)?;
     // maximum possibly needed is ONE_TRILLION divided by amount of ore for one fuel.
     // there might be a better solution though.
-    let ore_for_1_fuel = ONE_TRILLION / computer_ore_needed_for_fuel(1, &reactions);
+    let ore_for_1_fuel = ONE_TRILLION / plan.ore_needed(1);
 
     let (mut min, mut max) = (ore_for_1_fuel, ore_for_1_fuel * 2);
 
     while min != max {
         let middle = (min + max + 1) / 2;
-        match computer_ore_needed_for_fuel(middle, &reactions).cmp(&ONE_TRILLION) {
+        match plan.ore_needed(middle).cmp(&ONE_TRILLION) {
             Ordering::Equal => return Ok(middle),
             Ordering::Greater => max = (min + max) / 2,
             Ordering::Less => min = middle,
@@ -109,7 +194,17 @@ pub fn part_2(input: &str) -> Result<usize> {
 
 #[cfg(test)]
 mod tests {
-    use crate::part_1;
+    use crate::{ore_for_fuel_batches, parse_input, part_1, part_2, ProductionPlan};
+
+    const EXAMPLE_13312: &str = "157 ORE => 5 NZVS
+165 ORE => 6 DCFZ
+44 XJWVT, 5 KHKGT, 1 QDVJ, 29 NZVS, 9 GPVTF, 48 HKGWZ => 1 FUEL
+12 HKGWZ, 1 GPVTF, 8 PSHF => 9 QDVJ
+179 ORE => 7 PSHF
+177 ORE => 5 HKGWZ
+7 DCFZ, 7 PSHF => 2 XJWVT
+165 ORE => 2 GPVTF
+3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT";
 
     #[test]
     fn test_part1() {
@@ -148,20 +243,42 @@ mod tests {
     #[test]
     fn test_another_2_part1() {
         env_logger::try_init().ok();
-        assert_eq!(
-            part_1(
-                "157 ORE => 5 NZVS
-165 ORE => 6 DCFZ
-44 XJWVT, 5 KHKGT, 1 QDVJ, 29 NZVS, 9 GPVTF, 48 HKGWZ => 1 FUEL
-12 HKGWZ, 1 GPVTF, 8 PSHF => 9 QDVJ
-179 ORE => 7 PSHF
-177 ORE => 5 HKGWZ
-7 DCFZ, 7 PSHF => 2 XJWVT
-165 ORE => 2 GPVTF
-3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT"
-            )
-            .unwrap(),
-            13312
+        assert_eq!(part_1(EXAMPLE_13312).unwrap(), 13312)
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part_2(EXAMPLE_13312).unwrap(), 82892753);
+    }
+
+    #[test]
+    fn test_production_plan_errors() {
+        let plan = |input: &str| ProductionPlan::new(&parse_input(input).unwrap());
+
+        assert!(plan("1 ORE => 1 A\n1 B => 1 FUEL").is_err());
+        assert!(plan("1 ORE, 1 B => 1 A\n1 A => 1 B\n1 A => 1 FUEL").is_err());
+    }
+
+    #[test]
+    fn test_fuel_batches() {
+        let reactions = parse_input(
+            "9 ORE => 2 A
+8 ORE => 3 B
+7 ORE => 5 C
+3 A, 4 B => 1 AB
+5 B, 7 C => 1 BC
+4 C, 1 A => 1 CA
+2 AB, 3 BC, 4 CA => 1 FUEL",
         )
+        .unwrap();
+        let plan = ProductionPlan::new(&reactions).unwrap();
+
+        let bulk = plan.ore_needed(10);
+        let batched = ore_for_fuel_batches(2, 5, &reactions);
+        let independent = 5 * plan.ore_needed(2);
+
+        assert!(bulk <= batched, "{} <= {}", bulk, batched);
+        assert!(batched <= independent, "{} <= {}", batched, independent);
+        assert_eq!(ore_for_fuel_batches(1, 1, &reactions), 165);
     }
 }
@@ -0,0 +1,15 @@
+use day14::{part_1, part_2};
+
+#[test]
+fn test_puzzle_answers() {
+    let input = match aoc_io::try_read_input(14) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 14 is missing");
+            return;
+        }
+    };
+
+    assert_eq!(part_1(&input).unwrap(), 612880);
+    assert_eq!(part_2(&input).unwrap(), 2509120);
+}
@@ -1,35 +1,89 @@
 use anyhow::{Context, Error, Result};
-use intcode_computer::IntcodeComputer;
+use intcode_computer::{Address, IntcodeComputer};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-pub fn part_1(input: &str) -> Result<i64> {
-    let memory = IntcodeComputer::parse_program(input)?;
-    let mut computer = IntcodeComputer::from_program_without_extra_memory(memory);
-    // restore gravity assist
-    computer.set_addr(1, 12)?;
-    computer.set_addr(2, 2)?;
+/// Runs `program` with `noun` and `verb` written to addresses 1 and 2,
+/// returning the value at `output_addr` once it halts.
+pub fn run_with_noun_verb(
+    program: &[i64],
+    noun: i64,
+    verb: i64,
+    output_addr: Address,
+) -> Result<i64> {
+    let mut computer = IntcodeComputer::from_program_without_extra_memory(program.to_vec());
+    computer.set_addr(1, noun)?;
+    computer.set_addr(2, verb)?;
 
     computer.run_until_halt()?;
-    // Output is provided at address 0
-    computer.get(0).context("Expected output")
+
+    computer.get(output_addr).context("Expected output")
 }
 
-pub fn part_2(input: &str) -> Result<i64> {
+pub fn part_1(input: &str) -> Result<i64> {
     let program = IntcodeComputer::parse_program(input)?;
+    // restore gravity assist, output is provided at address 0
+    run_with_noun_verb(&program, 12, 2, 0)
+}
 
-    for noun in 0..=99 {
-        for verb in 0..=99 {
-            // Clone computer here to avoid reparsing input.
-            let mut computer = IntcodeComputer::from_program_without_extra_memory(program.clone());
-            computer.set_addr(1, noun)?;
-            computer.set_addr(2, verb)?;
+/// Output the gravity assist program should produce.
+pub const GRAVITY_ASSIST_TARGET: i64 = 19_690_720;
 
-            computer.run_until_halt()?;
+/// Finds the `(noun, verb)` pair which makes the program output `target`.
+pub fn part_2(input: &str, target: i64) -> Result<(i64, i64)> {
+    let program = IntcodeComputer::parse_program(input)?;
 
-            if computer.get(0).context("Expected output")? == 19690720 {
-                return Ok((100 * noun) + verb);
+    for noun in 0..=99 {
+        for verb in 0..=99 {
+            if run_with_noun_verb(&program, noun, verb, 0)? == target {
+                return Ok((noun, verb));
             }
         }
     }
 
     Err(Error::msg("Value not found."))
 }
+
+/// Same as `part_2`, but tries the pairs in parallel.
+#[cfg(feature = "parallel")]
+pub fn part_2_parallel(input: &str, target: i64) -> Result<(i64, i64)> {
+    let program = IntcodeComputer::parse_program(input)?;
+
+    (0..100_i64)
+        .into_par_iter()
+        .flat_map(|noun| (0..100_i64).into_par_iter().map(move |verb| (noun, verb)))
+        .find_map_any(
+            |(noun, verb)| match run_with_noun_verb(&program, noun, verb, 0) {
+                Ok(output) if output == target => Some(Ok((noun, verb))),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            },
+        )
+        .unwrap_or_else(|| Err(Error::msg("Value not found.")))
+}
+
+/// The puzzle answer, `100 * noun + verb` for the pair producing `GRAVITY_ASSIST_TARGET`.
+pub fn part_2_answer(input: &str) -> Result<i64> {
+    #[cfg(feature = "parallel")]
+    let (noun, verb) = part_2_parallel(input, GRAVITY_ASSIST_TARGET)?;
+    #[cfg(not(feature = "parallel"))]
+    let (noun, verb) = part_2(input, GRAVITY_ASSIST_TARGET)?;
+
+    Ok(100 * noun + verb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_with_noun_verb() {
+        // Adds the values at `noun` and `verb`, and stores the result at address 5.
+        let program = [1, 0, 0, 5, 99, 0];
+
+        assert_eq!(run_with_noun_verb(&program, 4, 0, 5).unwrap(), 99 + 1);
+        assert_eq!(run_with_noun_verb(&program, 4, 4, 5).unwrap(), 99 + 99);
+        assert_eq!(run_with_noun_verb(&program, 4, 4, 0).unwrap(), 1);
+        assert!(run_with_noun_verb(&program, 4, 4, 6).is_err());
+    }
+}
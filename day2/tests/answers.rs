@@ -0,0 +1,33 @@
+use day2::{part_1, part_2, part_2_answer, GRAVITY_ASSIST_TARGET};
+
+#[test]
+fn test_puzzle_answers() {
+    let input = match aoc_io::try_read_input(2) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 2 is missing");
+            return;
+        }
+    };
+
+    assert_eq!(part_1(&input).unwrap(), 4330636);
+    assert_eq!(part_2(&input, GRAVITY_ASSIST_TARGET).unwrap(), (60, 86));
+    assert_eq!(part_2_answer(&input).unwrap(), 6086);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_search() {
+    let input = match aoc_io::try_read_input(2) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 2 is missing");
+            return;
+        }
+    };
+
+    assert_eq!(
+        day2::part_2_parallel(&input, GRAVITY_ASSIST_TARGET).unwrap(),
+        part_2(&input, GRAVITY_ASSIST_TARGET).unwrap()
+    );
+}
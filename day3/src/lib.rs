@@ -1,40 +1,72 @@
 use anyhow::{bail, Context, Error, Result};
+use num_traits::{PrimInt, Signed};
 
 use std::cmp;
 use std::collections::{HashMap, HashSet};
-use std::ops::RangeInclusive;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::iter;
+use std::num::ParseIntError;
+use std::ops::{Add, RangeInclusive, Sub};
 use std::str::FromStr;
 
+/// Signed integers which can be used as coordinates (and step counts) of a wire.
+pub trait Coordinate: PrimInt + Signed + Hash + Debug + FromStr<Err = ParseIntError> {}
+
+impl<T: PrimInt + Signed + Hash + Debug + FromStr<Err = ParseIntError>> Coordinate for T {}
+
+/// Iterates over an inclusive range of coordinates, `RangeInclusive<T>` is only an iterator for concrete types.
+fn iter_range<T: Coordinate>(range: RangeInclusive<T>) -> impl Iterator<Item = T> {
+    let (start, end) = range.into_inner();
+    let first = if start <= end { Some(start) } else { None };
+
+    iter::successors(
+        first,
+        move |&i| if i < end { Some(i + T::one()) } else { None },
+    )
+}
+
 #[derive(Debug, Clone)]
-pub enum Direction {
-    Up(i32),
-    Down(i32),
-    Left(i32),
-    Right(i32),
+pub enum Direction<T = i64> {
+    Up(T),
+    Down(T),
+    Left(T),
+    Right(T),
+    UpRight(T),
+    UpLeft(T),
+    DownRight(T),
+    DownLeft(T),
 }
 
-impl FromStr for Direction {
+impl<T: Coordinate> FromStr for Direction<T> {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
         use Direction::*;
 
-        let direction = s
-            .chars()
-            .next()
-            .context("Direction should not be an empty string")?;
+        if s.is_empty() {
+            bail!("Direction should not be an empty string");
+        }
+
+        let split = s
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .context("Direction should contain numbers after letter")?;
+        let (direction, n_steps) = s.split_at(split);
 
-        let n_steps: i32 = s
-            .get(1..)
-            .context("Direction should contain numbers after letter")?
-            .parse()?;
+        let n_steps: T = n_steps
+            .parse()
+            .with_context(|| format!("Invalid number of steps in `{}`", s))?;
 
         let result = match direction {
-            'U' => Up(n_steps),
-            'D' => Down(n_steps),
-            'L' => Left(n_steps),
-            'R' => Right(n_steps),
-            _ => bail!("Unknown direction `{}`", direction),
+            "U" => Up(n_steps),
+            "D" => Down(n_steps),
+            "L" => Left(n_steps),
+            "R" => Right(n_steps),
+            "NE" => UpRight(n_steps),
+            "NW" => UpLeft(n_steps),
+            "SE" => DownRight(n_steps),
+            "SW" => DownLeft(n_steps),
+            _ => bail!("Unknown direction `{}` in `{}`", direction, s),
         };
 
         Ok(result)
@@ -42,61 +74,102 @@ impl FromStr for Direction {
 }
 
 #[derive(Eq, Ord, PartialOrd, PartialEq, Hash, Copy, Clone, Debug)]
-pub struct Point(i32, i32);
+pub struct Point<T = i64>(T, T);
 
-impl Point {
-    pub fn x(&self) -> i32 {
+impl<T: Coordinate> Point<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Point(x, y)
+    }
+
+    pub fn x(&self) -> T {
         self.0
     }
 
-    pub fn y(&self) -> i32 {
+    pub fn y(&self) -> T {
         self.1
     }
 
     pub fn is_origin(&self) -> bool {
-        (self.x(), self.y()) == (0, 0)
+        self.x().is_zero() && self.y().is_zero()
     }
 
-    pub fn shift_in(&self, direction: &Direction) -> Point {
+    pub fn shift_in(&self, direction: &Direction<T>) -> Point<T> {
         match direction {
             Direction::Up(n) => Point(self.0, self.1 + *n),
             Direction::Down(n) => Point(self.0, self.1 - *n),
             Direction::Left(n) => Point(self.0 - *n, self.1),
             Direction::Right(n) => Point(self.0 + *n, self.1),
+            Direction::UpRight(n) => Point(self.0 + *n, self.1 + *n),
+            Direction::UpLeft(n) => Point(self.0 - *n, self.1 + *n),
+            Direction::DownRight(n) => Point(self.0 + *n, self.1 - *n),
+            Direction::DownLeft(n) => Point(self.0 - *n, self.1 - *n),
         }
     }
 
-    pub fn distance(&self, other: &Point) -> i32 {
+    pub fn distance(&self, other: &Point<T>) -> T {
         (self.x() - other.x()).abs() + (self.y() - other.y()).abs()
     }
 
-    pub fn manhattan_distance(&self) -> i32 {
+    /// Number of wire steps between two points on the same (possibly diagonal) line.
+    pub fn steps_to(&self, other: &Point<T>) -> T {
+        cmp::max((self.x() - other.x()).abs(), (self.y() - other.y()).abs())
+    }
+
+    pub fn manhattan_distance(&self) -> T {
         self.0.abs() + self.1.abs()
     }
 }
 
+impl<T: Coordinate> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Point<T>) -> Point<T> {
+        Point(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl<T: Coordinate> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, rhs: Point<T>) -> Point<T> {
+        Point(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
 #[derive(Eq, Ord, PartialOrd, PartialEq, Hash, Copy, Clone, Debug)]
-pub struct Line(Point, Point);
+pub struct Line<T = i64>(Point<T>, Point<T>);
+
+impl<T: Coordinate> Line<T> {
+    pub fn new(start: Point<T>, end: Point<T>) -> Self {
+        Line(start, end)
+    }
+
+    pub fn start(&self) -> Point<T> {
+        self.0
+    }
+
+    pub fn end(&self) -> Point<T> {
+        self.1
+    }
 
-impl Line {
-    pub fn xs(&self) -> RangeInclusive<i32> {
+    pub fn xs(&self) -> RangeInclusive<T> {
         let min = cmp::min(self.0.x(), self.1.x());
         let max = cmp::max(self.0.x(), self.1.x());
         min..=max
     }
 
-    pub fn ys(&self) -> RangeInclusive<i32> {
+    pub fn ys(&self) -> RangeInclusive<T> {
         let min = cmp::min(self.0.y(), self.1.y());
         let max = cmp::max(self.0.y(), self.1.y());
         min..=max
     }
 
-    pub fn len(&self) -> i32 {
-        self.0.distance(&self.1)
+    pub fn len(&self) -> T {
+        self.0.steps_to(&self.1)
     }
 
-    pub fn distance_from_point(&self, p: &Point) -> i32 {
-        self.0.distance(p)
+    pub fn distance_from_point(&self, p: &Point<T>) -> T {
+        self.0.steps_to(p)
     }
 
     pub fn is_horizontal(&self) -> bool {
@@ -107,26 +180,43 @@ impl Line {
         self.0.x() == self.1.x()
     }
 
-    pub fn intersects_line(&self, other: &Line) -> Option<Point> {
+    pub fn is_diagonal(&self) -> bool {
+        !self.is_horizontal() && !self.is_vertical()
+    }
+
+    /// Every lattice point on the line, from start to end.
+    pub fn points(&self) -> impl Iterator<Item = Point<T>> {
+        let start = self.0;
+        let dx = (self.1.x() - self.0.x()).signum();
+        let dy = (self.1.y() - self.0.y()).signum();
+
+        iter_range(T::zero()..=self.len())
+            .map(move |i| Point(start.x() + dx * i, start.y() + dy * i))
+    }
+
+    /// Lines involving a 45° segment only meet on lattice points, diagonals crossing
+    /// "between" points (like an X drawn over a single square) do not count.
+    pub fn intersects_line(&self, other: &Line<T>) -> Option<Point<T>> {
+        if self.is_diagonal() || other.is_diagonal() {
+            return self
+                .points()
+                .find(|p| !p.is_origin() && other.intersects_point(p));
+        }
+
         if self.is_horizontal() {
             // assuming this is not the same line
             if other.is_horizontal() {
                 return None;
             }
 
-            // other line is vertical, so `.xs()` start == end
-            let other_x = other.xs().start().clone();
-
-            if self.xs().contains(&other_x) {
-                // search for y's intersection
-                for y in other.ys() {
-                    if self.ys().contains(&y) {
-                        let intersection = Point(other_x, y);
-                        if !intersection.is_origin() {
-                            return Some(intersection);
-                        }
-                    }
-                }
+            // other line is vertical, so `.xs()` start == end, and `.ys()` is a single y for this line.
+            let intersection = Point(other.0.x(), self.0.y());
+
+            if self.xs().contains(&intersection.x())
+                && other.ys().contains(&intersection.y())
+                && !intersection.is_origin()
+            {
+                return Some(intersection);
             }
         }
 
@@ -136,48 +226,84 @@ impl Line {
                 return None;
             }
 
-            // other line is horizontal, so `.ys()` start == end
-            let other_y = other.ys().start().clone();
-
-            if self.ys().contains(&other_y) {
-                // search for y's intersection
-                for x in other.xs() {
-                    // exclude origin
-                    if self.xs().contains(&x) {
-                        let intersection = Point(x, other_y);
-                        if !intersection.is_origin() {
-                            return Some(intersection);
-                        }
-                    }
-                }
+            // other line is horizontal, so `.ys()` start == end, and `.xs()` is a single x for this line.
+            let intersection = Point(self.0.x(), other.0.y());
+
+            if self.ys().contains(&intersection.y())
+                && other.xs().contains(&intersection.x())
+                && !intersection.is_origin()
+            {
+                return Some(intersection);
             }
         }
         return None;
     }
 
-    pub fn intersects_point(&self, other: &Point) -> bool {
-        self.xs().contains(&other.x()) && self.ys().contains(&other.y())
+    /// When both lines lie on the same horizontal, vertical or diagonal axis, returns every lattice point they share
+    /// (excluding the origin). Returns nothing for lines that are not collinear.
+    pub fn overlapping_points(&self, other: &Line<T>) -> Vec<Point<T>> {
+        if self.is_diagonal() && other.is_diagonal() {
+            // Diagonals that are not collinear share one point at most.
+            let shared: Vec<Point<T>> = self
+                .points()
+                .filter(|p| other.intersects_point(p))
+                .collect();
+
+            if shared.len() < 2 {
+                return vec![];
+            }
+
+            return shared.into_iter().filter(|p| !p.is_origin()).collect();
+        }
+
+        let collinear =
+            (self.is_horizontal() && other.is_horizontal() && self.0.y() == other.0.y())
+                || (self.is_vertical() && other.is_vertical() && self.0.x() == other.0.x());
+
+        if !collinear {
+            return vec![];
+        }
+
+        let xs = cmp::max(*self.xs().start(), *other.xs().start())
+            ..=cmp::min(*self.xs().end(), *other.xs().end());
+        let ys = cmp::max(*self.ys().start(), *other.ys().start())
+            ..=cmp::min(*self.ys().end(), *other.ys().end());
+
+        iter_range(xs)
+            .flat_map(|x| iter_range(ys.clone()).map(move |y| Point(x, y)))
+            .filter(|p| !p.is_origin())
+            .collect()
+    }
+
+    pub fn intersects_point(&self, other: &Point<T>) -> bool {
+        let in_bounds = self.xs().contains(&other.x()) && self.ys().contains(&other.y());
+
+        if self.is_diagonal() {
+            return in_bounds && (other.x() - self.0.x()).abs() == (other.y() - self.0.y()).abs();
+        }
+
+        in_bounds
     }
 }
 
-struct Wire(Vec<Direction>);
+pub struct Wire<T = i64>(Vec<Direction<T>>);
 
-impl FromStr for Wire {
+impl<T: Coordinate> FromStr for Wire<T> {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let inner: Vec<Direction> = s
+        let inner: Vec<Direction<T>> = s
             .trim()
             .split(",")
             .map(Direction::from_str)
-            .collect::<Result<Vec<Direction>>>()?;
+            .collect::<Result<Vec<Direction<T>>>>()?;
         Ok(Wire(inner))
     }
 }
 
-impl Wire {
-    pub fn iter_lines(&self) -> impl Iterator<Item = Line> + '_ {
-        let mut position = Point(0, 0);
+impl<T: Coordinate> Wire<T> {
+    pub fn iter_lines(&self) -> impl Iterator<Item = Line<T>> + '_ {
+        let mut position = Point(T::zero(), T::zero());
         let mut iter = self.0.iter();
 
         std::iter::from_fn(move || {
@@ -192,21 +318,75 @@ impl Wire {
     }
 }
 
-fn find_intersections(lines_1: &Vec<Line>, lines_2: &Vec<Line>) -> HashSet<Point> {
+/// Adds every point where `l1` and `l2` meet (excluding the origin) to `intersections`.
+fn add_crossings<T: Coordinate>(l1: &Line<T>, l2: &Line<T>, intersections: &mut HashSet<Point<T>>) {
+    if let Some(point) = l1.intersects_line(l2) {
+        intersections.insert(point);
+    }
+    intersections.extend(l1.overlapping_points(l2));
+}
+
+/// Compares every segment of one wire with every segment of the other.
+#[cfg(test)]
+fn find_intersections_brute_force<T: Coordinate>(
+    lines_1: &[Line<T>],
+    lines_2: &[Line<T>],
+) -> HashSet<Point<T>> {
     let mut intersections = HashSet::new();
 
     for l1 in lines_1.iter() {
         for l2 in lines_2.iter() {
-            if let Some(point) = l1.intersects_line(&l2) {
-                intersections.insert(point);
+            add_crossings(l1, l2, &mut intersections);
+        }
+    }
+
+    intersections
+}
+
+/// Sweeps a vertical line from left to right, keeping the segments of each wire it currently crosses.
+/// A segment is only compared with the active segments of the other wire whose `ys` overlap its own,
+/// so segments that are far apart are never compared.
+fn find_intersections<T: Coordinate>(
+    lines_1: &[Line<T>],
+    lines_2: &[Line<T>],
+) -> HashSet<Point<T>> {
+    let mut segments: Vec<(usize, &Line<T>)> = lines_1
+        .iter()
+        .map(|line| (0, line))
+        .chain(lines_2.iter().map(|line| (1, line)))
+        .collect();
+    segments.sort_by_key(|(_, line)| *line.xs().start());
+
+    let mut active: [Vec<&Line<T>>; 2] = [vec![], vec![]];
+    let mut intersections = HashSet::new();
+
+    for (wire, line) in segments {
+        let x = *line.xs().start();
+        let ys = line.ys();
+
+        let others = &mut active[1 - wire];
+        others.retain(|other| *other.xs().end() >= x);
+
+        for other in others.iter() {
+            if other.ys().start() > ys.end() || ys.start() > other.ys().end() {
+                continue;
+            }
+
+            // Keep the same order as the brute force version.
+            match wire {
+                0 => add_crossings(line, other, &mut intersections),
+                _ => add_crossings(other, line, &mut intersections),
             }
         }
+
+        active[wire].push(line);
     }
 
     intersections
 }
 
-pub fn part_1(input: &str) -> Result<i32> {
+/// Parses the input, which should contain exactly two wires, into their lines.
+fn parse_two_wires(input: &str) -> Result<(Vec<Line>, Vec<Line>)> {
     let wires = input
         .lines()
         .map(Wire::from_str)
@@ -216,40 +396,120 @@ pub fn part_1(input: &str) -> Result<i32> {
         bail!("Expected two wires exactly, found {}.", wires.len())
     }
 
-    let wire_1 = &wires[0];
-    let wire_2 = &wires[1];
+    let lines_1 = wires[0].iter_lines().collect();
+    let lines_2 = wires[1].iter_lines().collect();
 
-    let lines_1 = wire_1.iter_lines().collect();
-    let lines_2 = wire_2.iter_lines().collect();
+    Ok((lines_1, lines_2))
+}
 
-    let intersections = find_intersections(&lines_1, &lines_2);
+/// Smallest number of steps along the wire to reach `point`, considering every segment that passes
+/// through it. `point` must be on the wire.
+fn steps_to<T: Coordinate>(lines: &[Line<T>], point: &Point<T>) -> T {
+    lines
+        .iter()
+        .scan(T::zero(), |walked, line| {
+            let start = *walked;
+            *walked = *walked + line.len();
+            Some((start, line))
+        })
+        .filter(|(_, line)| line.intersects_point(point))
+        .map(|(start, line)| start + line.distance_from_point(point))
+        .min()
+        .expect("Point should be on the wire")
+}
 
-    if intersections.is_empty() {
-        bail!("Wires do not intersect.")
+/// Every point where the two wires cross, with its manhattan distance from the origin and the
+/// combined number of steps both wires take to reach it. Sorted by distance, then by steps.
+pub fn intersections(input: &str) -> Result<Vec<(Point, i64, i64)>> {
+    let (lines_1, lines_2) = parse_two_wires(input)?;
+
+    let mut result: Vec<(Point, i64, i64)> = find_intersections(&lines_1, &lines_2)
+        .into_iter()
+        .map(|p| {
+            let steps = steps_to(&lines_1, &p) + steps_to(&lines_2, &p);
+            (p, p.manhattan_distance(), steps)
+        })
+        .collect();
+
+    result.sort_by_key(|&(p, distance, steps)| (distance, steps, p));
+
+    Ok(result)
+}
+
+/// Counts the distinct points where a single wire crosses itself. Segments next to each other always
+/// share an endpoint, which isn't a crossing, but they still overlap when the wire doubles back on itself.
+/// Like everywhere else, the origin does not count as a crossing.
+pub fn self_intersections(wire: &str) -> Result<usize> {
+    let wire: Wire = wire.parse()?;
+    let lines: Vec<Line> = wire.iter_lines().collect();
+
+    let mut intersections = HashSet::new();
+    for (i, l1) in lines.iter().enumerate() {
+        if let Some(next) = lines.get(i + 1) {
+            let mut shared = HashSet::new();
+            add_crossings(l1, next, &mut shared);
+            shared.remove(&l1.1);
+            intersections.extend(shared);
+        }
+
+        for l2 in lines.iter().skip(i + 2) {
+            add_crossings(l1, l2, &mut intersections);
+        }
     }
 
-    intersections
-        .iter()
-        .map(|p| p.manhattan_distance())
-        .min()
-        .ok_or_else(|| Error::msg("Expected a minimum"))
+    Ok(intersections.len())
 }
 
-pub fn part_2(input: &str) -> Result<i32> {
+/// Like `part_1`, but for any number of wires. Only crossings of two distinct wires count,
+/// a wire crossing itself is ignored.
+pub fn closest_intersection_n(input: &str) -> Result<i64> {
     let wires = input
         .lines()
         .map(Wire::from_str)
         .collect::<Result<Vec<Wire>>>()?;
 
-    if wires.len() != 2 {
-        bail!("Expected two wires exactly, found {}.", wires.len())
+    if wires.len() < 2 {
+        bail!("Expected at least two wires, found {}.", wires.len())
     }
 
-    let wire_1: &Wire = &wires[0];
-    let wire_2: &Wire = &wires[1];
+    let lines: Vec<Vec<Line>> = wires.iter().map(|w| w.iter_lines().collect()).collect();
 
-    let lines_1 = wire_1.iter_lines().collect();
-    let lines_2 = wire_2.iter_lines().collect();
+    let mut closest = None;
+    for (i, lines_1) in lines.iter().enumerate() {
+        for lines_2 in lines.iter().skip(i + 1) {
+            let distance = find_intersections(lines_1, lines_2)
+                .iter()
+                .map(|p| p.manhattan_distance())
+                .min();
+
+            closest = match (closest, distance) {
+                (Some(c), Some(d)) => Some(cmp::min(c, d)),
+                (c, d) => c.or(d),
+            };
+        }
+    }
+
+    closest.context("Wires do not intersect.")
+}
+
+pub fn part_1(input: &str) -> Result<i64> {
+    let (lines_1, lines_2) = parse_two_wires(input)?;
+
+    let intersections = find_intersections(&lines_1, &lines_2);
+
+    if intersections.is_empty() {
+        bail!("Wires do not intersect.")
+    }
+
+    intersections
+        .iter()
+        .map(|p| p.manhattan_distance())
+        .min()
+        .ok_or_else(|| Error::msg("Expected a minimum"))
+}
+
+pub fn part_2(input: &str) -> Result<i64> {
+    let (lines_1, lines_2) = parse_two_wires(input)?;
 
     let intersections = find_intersections(&lines_1, &lines_2);
 
@@ -260,23 +520,7 @@ pub fn part_2(input: &str) -> Result<i32> {
     let mut intersection_to_len = HashMap::new();
 
     for intersection in intersections.iter() {
-        let mut sum = 0;
-        for line in lines_1.iter() {
-            if line.intersects_point(intersection) {
-                sum += line.distance_from_point(intersection);
-                break;
-            } else {
-                sum += line.len()
-            }
-        }
-        for line in lines_2.iter() {
-            if line.intersects_point(intersection) {
-                sum += line.distance_from_point(intersection);
-                break;
-            } else {
-                sum += line.len()
-            }
-        }
+        let sum = steps_to(&lines_1, intersection) + steps_to(&lines_2, intersection);
         intersection_to_len.insert(intersection, sum);
     }
 
@@ -286,9 +530,164 @@ pub fn part_2(input: &str) -> Result<i32> {
         .expect("We've checked intersections is not empty."))
 }
 
+/// Rendered grids are downsampled so that neither side is longer than this many characters.
+pub const RENDER_MAX_DIMENSION: i64 = 200;
+
+/// Draws the wires on a character grid, with `+` where different wires cross and `o` at the origin.
+/// Up is the top of the grid. Grids larger than `RENDER_MAX_DIMENSION` are downsampled, in which case
+/// every character stands for a square of points.
+pub fn render(input: &str) -> Result<String> {
+    let wires = input
+        .lines()
+        .map(Wire::from_str)
+        .collect::<Result<Vec<Wire>>>()?;
+
+    let lines: Vec<Vec<Line>> = wires.iter().map(|w| w.iter_lines().collect()).collect();
+
+    let (mut min_x, mut max_x, mut min_y, mut max_y) = (0, 0, 0, 0);
+    for line in lines.iter().flatten() {
+        min_x = cmp::min(min_x, *line.xs().start());
+        max_x = cmp::max(max_x, *line.xs().end());
+        min_y = cmp::min(min_y, *line.ys().start());
+        max_y = cmp::max(max_y, *line.ys().end());
+    }
+
+    let longest_side = cmp::max(max_x - min_x, max_y - min_y) + 1;
+    let scale = (longest_side + RENDER_MAX_DIMENSION - 1) / RENDER_MAX_DIMENSION;
+
+    let width = ((max_x - min_x) / scale + 1) as usize;
+    let height = ((max_y - min_y) / scale + 1) as usize;
+
+    // Each cell remembers which wire drew it, so only crossings of different wires are marked.
+    let mut grid: Vec<Vec<Option<(usize, char)>>> = vec![vec![None; width]; height];
+    let cell = |p: &Point| {
+        (
+            ((max_y - p.y()) / scale) as usize,
+            ((p.x() - min_x) / scale) as usize,
+        )
+    };
+
+    for (wire, wire_lines) in lines.iter().enumerate() {
+        for line in wire_lines {
+            let c = if line.is_horizontal() {
+                '-'
+            } else if line.is_vertical() {
+                '|'
+            } else if (line.1.x() - line.0.x()).signum() == (line.1.y() - line.0.y()).signum() {
+                '/'
+            } else {
+                '\\'
+            };
+
+            for p in line.points() {
+                let (row, col) = cell(&p);
+                grid[row][col] = match grid[row][col] {
+                    Some((other, _)) if other != wire => Some((wire, '+')),
+                    Some((_, '+')) => Some((wire, '+')),
+                    _ => Some((wire, c)),
+                };
+            }
+        }
+    }
+
+    let (row, col) = cell(&Point(0, 0));
+    grid[row][col] = Some((0, 'o'));
+
+    Ok(grid
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|c| c.map_or(' ', |(_, c)| c))
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[test]
+    fn test_point_arithmetic() {
+        let a = Point::new(3, -4);
+        let b = Point::new(-1, 2);
+
+        assert_eq!(a + b, Point::new(2, -2));
+        assert_eq!(a - b, Point::new(4, -6));
+        assert_eq!((a - b).manhattan_distance(), a.distance(&b));
+
+        let line = Line::new(a, a + Point::new(0, 5));
+        assert!(line.is_vertical());
+        assert_eq!(line.end() - line.start(), Point::new(0, 5));
+    }
+
+    #[test]
+    fn test_coordinates_beyond_i32() {
+        // The wires only meet at (3000000000, 5), which is out of range for `i32`.
+        let wires = "R3000000000,U5\nU5,R3000000000";
+
+        assert_eq!(part_1(wires).unwrap(), 3_000_000_005);
+        assert_eq!(part_2(wires).unwrap(), 6_000_000_010);
+        assert!("R3000000000".parse::<Wire<i32>>().is_err());
+    }
+
+    #[test]
+    fn test_small_coordinate_type() {
+        let wire: Wire<i16> = "R8,U5,L5,D3".parse().unwrap();
+        let lines: Vec<Line<i16>> = wire.iter_lines().collect();
+
+        assert_eq!(lines[3], Line::new(Point::new(3, 5), Point::new(3, 2)));
+        assert_eq!(steps_to(&lines, &Point::new(3, 3)), 20);
+    }
+
+    /// Small xorshift generator, keeps the random wires reproducible.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    fn random_wire(rng: &mut XorShift) -> Vec<Line> {
+        const DIRECTIONS: [&str; 8] = ["U", "D", "L", "R", "NE", "NW", "SE", "SW"];
+
+        let wire = (0..=rng.below(8))
+            .map(|_| {
+                let direction = DIRECTIONS[rng.below(DIRECTIONS.len() as u64) as usize];
+                format!("{}{}", direction, 1 + rng.below(6))
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+
+        wire.parse::<Wire>().unwrap().iter_lines().collect()
+    }
+
+    #[test]
+    fn test_sweep_matches_brute_force() {
+        let mut rng = XorShift(0x2019_1203);
+
+        for _ in 0..2000 {
+            let lines_1 = random_wire(&mut rng);
+            let lines_2 = random_wire(&mut rng);
+
+            assert_eq!(
+                find_intersections(&lines_1, &lines_2),
+                find_intersections_brute_force(&lines_1, &lines_2),
+                "{:?} and {:?}",
+                lines_1,
+                lines_2
+            );
+        }
+    }
+
     #[test]
     fn test_lines_horizontal_vertical() {
         let l1 = Line(Point(0, 0), Point(0, 10));
@@ -312,6 +711,145 @@ mod tests {
         assert_eq!(l1.intersects_line(&l2), Some(Point(0, 5)));
     }
 
+    #[test]
+    fn test_lines_overlapping() {
+        let l1 = Line(Point(0, 5), Point(10, 5));
+        let l2 = Line(Point(12, 5), Point(4, 5));
+        let expected: Vec<Point> = (4..=10).map(|x| Point(x, 5)).collect();
+
+        assert_eq!(l1.overlapping_points(&l2), expected);
+        assert_eq!(l2.overlapping_points(&l1), expected);
+
+        // Parallel, but not on the same axis.
+        assert!(l1
+            .overlapping_points(&Line(Point(0, 6), Point(10, 6)))
+            .is_empty());
+        // Perpendicular lines are handled by `intersects_line`.
+        assert!(l1
+            .overlapping_points(&Line(Point(5, 0), Point(5, 10)))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_lines_overlapping_excludes_origin() {
+        let l1 = Line(Point(0, 0), Point(0, 3));
+        let l2 = Line(Point(0, 2), Point(0, -2));
+
+        assert_eq!(l1.overlapping_points(&l2), vec![Point(0, 1), Point(0, 2)]);
+    }
+
+    #[test]
+    fn test_parse_diagonal_directions() {
+        assert!(matches!(
+            "NE5".parse::<Direction>(),
+            Ok(Direction::UpRight(5))
+        ));
+        assert!(matches!(
+            "SW12".parse::<Direction>(),
+            Ok(Direction::DownLeft(12))
+        ));
+        assert!(matches!("U3".parse::<Direction>(), Ok(Direction::Up(3))));
+
+        let err = "NX5".parse::<Direction>().unwrap_err();
+        assert!(err.to_string().contains("`NX`"));
+        assert!("NE".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_lines_diagonal_horizontal() {
+        let diagonal = Line(Point(0, 0), Point(4, 4));
+        let horizontal = Line(Point(0, 2), Point(4, 2));
+
+        assert_eq!(diagonal.intersects_line(&horizontal), Some(Point(2, 2)));
+        assert_eq!(horizontal.intersects_line(&diagonal), Some(Point(2, 2)));
+        assert!(diagonal.intersects_point(&Point(3, 3)));
+        assert!(!diagonal.intersects_point(&Point(3, 2)));
+    }
+
+    #[test]
+    fn test_diagonal_wires() {
+        // The diagonal wire crosses the other's last segment at (2, 2).
+        assert_eq!(part_1("NE4\nR4,U2,L4").unwrap(), 4);
+        // 2 steps along the diagonal, 4 + 2 + 2 along the other wire.
+        assert_eq!(part_2("NE4\nR4,U2,L4").unwrap(), 10);
+    }
+
+    #[test]
+    fn test_part_1_overlapping_wires() {
+        // The wires share the segment from the origin to (4, 0), so the closest crossing is (1, 0).
+        assert_eq!(part_1("R5,U2\nR4,U3").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_intersections() {
+        assert_eq!(
+            intersections("R8,U5,L5,D3\nU7,R6,D4,L4").unwrap(),
+            vec![(Point(3, 3), 6, 40), (Point(6, 5), 11, 30)]
+        );
+    }
+
+    #[test]
+    fn test_steps_to_revisited_point() {
+        // Passes through (2, 0) after 2 steps, and again after 10.
+        let wire: Wire = "R4,U2,L2,D4".parse().unwrap();
+        let lines: Vec<Line> = wire.iter_lines().collect();
+
+        assert_eq!(steps_to(&lines, &Point(2, 0)), 2);
+        assert_eq!(part_2("R4,U2,L2,D4\nD1,R2,U3").unwrap(), 6);
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = render("R8,U5,L5,D3\nU7,R6,D4,L4").unwrap();
+        let rows: Vec<&str> = rendered.lines().collect();
+
+        // The origin is at the bottom left, (3, 3) is three rows up and three columns right.
+        assert_eq!(rows.len(), 8);
+        assert_eq!(rows[7].chars().next(), Some('o'));
+        assert_eq!(rows[4].chars().nth(3), Some('+'));
+        assert_eq!(rendered.matches('+').count(), 2);
+    }
+
+    #[test]
+    fn test_render_downsamples() {
+        let rendered = render("R1000,U3000\nU10").unwrap();
+
+        assert!(rendered.lines().count() <= RENDER_MAX_DIMENSION as usize);
+        assert!(rendered
+            .lines()
+            .all(|row| row.len() <= RENDER_MAX_DIMENSION as usize));
+        assert!(rendered.contains('o'));
+    }
+
+    #[test]
+    fn test_closest_intersection_n() {
+        let two_wires = "R8,U5,L5,D3\nU7,R6,D4,L4";
+        assert_eq!(closest_intersection_n(two_wires).unwrap(), 6);
+
+        // The third wire crosses the first right next to the origin, but never meets the second.
+        let three_wires = "R8,U5,L5,D3\nU7,R6,D4,L4\nD1,R1,U3";
+        assert_eq!(closest_intersection_n(three_wires).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_closest_intersection_n_ignores_self_crossings() {
+        // The first wire crosses itself at (2, 0), but the second wire never meets it.
+        assert!(closest_intersection_n("R4,U2,L2,D4\nL5").is_err());
+        assert!(closest_intersection_n("R4,U2,L2,D4").is_err());
+    }
+
+    #[test]
+    fn test_self_intersections() {
+        // A figure eight, crossing itself once at (1, 0).
+        assert_eq!(self_intersections("R2,U2,L1,D4,L1,U2").unwrap(), 1);
+        // Only corners, which are shared by adjacent segments.
+        assert_eq!(self_intersections("R2,U2,L2,D1").unwrap(), 0);
+        // Doubling back overlaps the previous segment on (4, 0) to (7, 0), the turn itself doesn't count.
+        assert_eq!(self_intersections("R8,L4").unwrap(), 4);
+        assert_eq!(self_intersections("R8,L4,U1").unwrap(), 4);
+        assert!(self_intersections("X2").is_err());
+    }
+
     #[test]
     fn test_part_1() {
         assert_eq!(
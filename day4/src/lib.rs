@@ -1,7 +1,5 @@
 use anyhow::{bail, Context, Result};
 
-type Password = [u8; 6];
-
 pub fn parse_range(input: &str) -> Result<(u32, u32)> {
     let range: Vec<u32> = input
         .trim()
@@ -19,93 +17,90 @@ pub fn parse_range(input: &str) -> Result<(u32, u32)> {
     Ok((range[0], range[1]))
 }
 
-pub fn part_1(low: u32, hi: u32) -> u32 {
+/// Every password in the range that passes the rules, in ascending order.
+pub fn valid_passwords(low: u32, hi: u32, part2_rules: bool) -> Vec<u32> {
     (low..=hi)
-        .map(num_to_arr)
-        .filter(|&n| check_password(n))
-        .count() as u32
+        .filter(|&n| {
+            if part2_rules {
+                check_password_2(num_to_digits(n, &mut [0; 10]))
+            } else {
+                check_password(num_to_digits(n, &mut [0; 10]))
+            }
+        })
+        .collect()
+}
+
+pub fn part_1(low: u32, hi: u32) -> u32 {
+    valid_passwords(low, hi, false).len() as u32
 }
 
 pub fn part_2(low: u32, hi: u32) -> u32 {
-    (low..=hi)
-        .map(num_to_arr)
-        .filter(|&n| check_password_2(n))
-        .count() as u32
+    valid_passwords(low, hi, true).len() as u32
 }
 
-fn num_to_arr(mut n: u32) -> Password {
-    let mut res: Password = [0; 6];
-    for digit in res.iter_mut().rev() {
-        *digit = (n % 10) as u8;
+/// Writes the decimal digits of `n` (most significant first) to the end of `buffer`, and returns them.
+/// A `u32` has at most 10 digits, so this never needs to allocate.
+fn num_to_digits(mut n: u32, buffer: &mut [u8; 10]) -> &[u8] {
+    let mut start = buffer.len();
+
+    loop {
+        start -= 1;
+        buffer[start] = (n % 10) as u8;
         n /= 10;
+
+        if n == 0 {
+            return &buffer[start..];
+        }
     }
-    res
 }
 
-fn check_password_2(pass: Password) -> bool {
-    let mut largest_digit_seen = 0;
-    let mut following_digits_seen = false;
-    let mut digit_group_size = 1;
-    let mut previous_digit = None;
-
-    for &digit in pass.iter() {
-        if digit >= largest_digit_seen {
-            largest_digit_seen = digit;
+/// Checks the password rules on any number of digits.
+/// Digits must never decrease, and there must be a group of at least two equal adjacent digits.
+/// With `require_isolated_pair`, at least one such group must be exactly two digits long.
+pub fn check_digits(digits: &[u8], require_isolated_pair: bool) -> bool {
+    check_digit_groups(digits, |size| {
+        if require_isolated_pair {
+            size == 2
         } else {
-            return false;
-        }
-
-        // Check following digits
-        if let Some(prev) = previous_digit.take() {
-            if prev == digit {
-                digit_group_size += 1;
-            } else {
-                // seen a new number, check if condition was met.
-                if digit_group_size == 2 {
-                    following_digits_seen = true
-                }
-                digit_group_size = 1;
-            }
+            size >= 2
         }
+    })
+}
 
-        previous_digit = Some(digit);
-    }
-
-    // If last group was 2, it's still OK.
-    if following_digits_seen || digit_group_size == 2 {
-        true
-    } else {
-        false
-    }
+/// Like part 2, but the isolated group must be exactly `exact_group` digits long.
+pub fn check_number_with_group(n: u32, exact_group: usize) -> bool {
+    check_digit_groups(num_to_digits(n, &mut [0; 10]), |size| size == exact_group)
 }
 
-fn check_password(pass: Password) -> bool {
-    let mut largest_digit_seen = 0;
+/// Digits must never decrease, and at least one group of equal adjacent digits must satisfy `group_ok`.
+fn check_digit_groups(digits: &[u8], group_ok: impl Fn(usize) -> bool) -> bool {
     let mut following_digits_seen = false;
-    let mut previous_digit = None;
+    let mut digit_group_size = 1;
 
-    for &digit in pass.iter() {
-        if digit >= largest_digit_seen {
-            largest_digit_seen = digit;
-        } else {
+    for pair in digits.windows(2) {
+        if pair[0] > pair[1] {
             return false;
         }
 
-        // Check following digits
-        if let Some(prev) = previous_digit.take() {
-            if prev == digit {
-                following_digits_seen = true
-            }
+        if pair[0] == pair[1] {
+            digit_group_size += 1;
+        } else {
+            // seen a new number, check if condition was met.
+            following_digits_seen |= group_ok(digit_group_size);
+            digit_group_size = 1;
         }
-
-        previous_digit = Some(digit);
     }
 
-    if following_digits_seen {
-        true
-    } else {
-        false
-    }
+    // The last group is only checked here.
+    following_digits_seen || group_ok(digit_group_size)
+}
+
+fn check_password_2(pass: &[u8]) -> bool {
+    check_digits(pass, true)
+}
+
+fn check_password(pass: &[u8]) -> bool {
+    check_digits(pass, false)
 }
 
 #[cfg(test)]
@@ -113,17 +108,70 @@ mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("111110-111112\n").unwrap(), (111110, 111112));
+        assert!(parse_range("111110").is_err());
+        assert!(parse_range("1-2-3").is_err());
+
+        let (low, hi) = parse_range("111110-111112").unwrap();
+        assert_eq!(part_1(low, hi), 2);
+    }
+
+    #[test]
+    fn test_num_to_digits() {
+        for &n in &[0, 7, 10, 99, 100, 111_122, 654_321, 1_000_000, u32::MAX] {
+            let expected: Vec<u8> = n.to_string().bytes().map(|b| b - b'0').collect();
+            assert_eq!(num_to_digits(n, &mut [0; 10]), &expected[..], "{}", n);
+        }
+    }
+
     #[test]
     fn test_part_1() {
-        assert_eq!(check_password([1, 1, 1, 1, 1, 1]), true);
-        assert_eq!(check_password([2, 2, 3, 4, 5, 0]), false);
-        assert_eq!(check_password([1, 2, 3, 7, 8, 9]), false);
+        assert_eq!(check_password(&[1, 1, 1, 1, 1, 1]), true);
+        assert_eq!(check_password(&[2, 2, 3, 4, 5, 0]), false);
+        assert_eq!(check_password(&[1, 2, 3, 7, 8, 9]), false);
+    }
+
+    #[test]
+    fn test_check_digits_any_length() {
+        assert_eq!(check_digits(&[1, 1, 2, 3], false), true, "1123");
+        assert_eq!(check_digits(&[1, 1, 1, 3], true), false, "1113");
+        assert_eq!(check_digits(&[1, 2, 3, 4], false), false, "1234");
+
+        assert_eq!(
+            check_digits(&[1, 1, 1, 1, 2, 2, 3, 4], true),
+            true,
+            "11112234"
+        );
+        assert_eq!(
+            check_digits(&[1, 2, 3, 4, 5, 6, 7, 6], false),
+            false,
+            "12345676"
+        );
+        assert_eq!(part_1(11_111_110, 11_111_112), 2);
+    }
+
+    #[test]
+    fn test_valid_passwords() {
+        assert_eq!(valid_passwords(111110, 111112, false), vec![111111, 111112]);
+        assert_eq!(valid_passwords(111110, 111112, true), Vec::<u32>::new());
+        assert_eq!(valid_passwords(111120, 111122, true), vec![111122]);
+    }
+
+    #[test]
+    fn test_check_number_with_group() {
+        assert_eq!(check_number_with_group(111122, 2), true, "111122");
+        assert_eq!(check_number_with_group(123444, 2), false, "123444");
+        assert_eq!(check_number_with_group(111223, 3), true, "111223");
+        assert_eq!(check_number_with_group(112233, 3), false, "112233");
+        assert_eq!(check_number_with_group(111110, 3), false, "111110");
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(check_password_2([1, 1, 1, 1, 1, 1]), false, "111111");
-        assert_eq!(check_password_2([1, 2, 3, 4, 4, 4]), false, "123444");
-        assert_eq!(check_password_2([1, 1, 1, 1, 2, 2]), true, "111122");
+        assert_eq!(check_password_2(&[1, 1, 1, 1, 1, 1]), false, "111111");
+        assert_eq!(check_password_2(&[1, 2, 3, 4, 4, 4]), false, "123444");
+        assert_eq!(check_password_2(&[1, 1, 1, 1, 2, 2]), true, "111122");
     }
 }
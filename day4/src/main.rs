@@ -1,13 +1,9 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
+use aoc_io::read_stdin;
 use day4::{parse_range, part_1, part_2};
-use std::io::{stdin, Read};
 
 fn main() -> Result<()> {
-    let mut input = String::new();
-
-    stdin()
-        .read_to_string(&mut input)
-        .context("Failed to read input from stdin")?;
+    let input = read_stdin()?;
 
     let (low, hi) = parse_range(&input)?;
 
@@ -0,0 +1,17 @@
+use day4::{parse_range, part_1, part_2};
+
+#[test]
+fn test_puzzle_answers() {
+    let input = match aoc_io::try_read_input(4) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 4 is missing");
+            return;
+        }
+    };
+
+    let (low, hi) = parse_range(&input).unwrap();
+
+    assert_eq!(part_1(low, hi), 1246);
+    assert_eq!(part_2(low, hi), 814);
+}
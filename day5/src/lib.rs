@@ -1,20 +1,78 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use intcode_computer::IntcodeComputer;
 
-pub fn part_1(input: &str) -> Result<String> {
+/// System id of the ship's air conditioner unit.
+const AIR_CONDITIONER: i64 = 1;
+/// System id of the ship's thermal radiator controller.
+const THERMAL_RADIATOR: i64 = 5;
+
+/// Runs the diagnostic program with `id` as input, returning all of its outputs.
+pub fn run_with_system_id(input: &str, id: i64) -> Result<Vec<i64>> {
     let bytecode = IntcodeComputer::parse_program(input)?;
     let mut computer = IntcodeComputer::from_program_without_extra_memory(bytecode);
-    computer.write_to_input(vec![1])?;
+    computer.write_to_input([id])?;
     computer.run_until_halt()?;
 
-    Ok(format!("{:?}", computer.into_output()))
+    Ok(computer.into_output().into_iter().collect())
 }
 
-pub fn part_2(input: &str) -> Result<String> {
-    let bytecode = IntcodeComputer::parse_program(input)?;
-    let mut computer = IntcodeComputer::from_program_without_extra_memory(bytecode);
-    computer.write_to_input(vec![5])?;
-    computer.run_until_halt()?;
+pub fn part_1(input: &str) -> Result<Vec<i64>> {
+    run_with_system_id(input, AIR_CONDITIONER)
+}
+
+pub fn part_2(input: &str) -> Result<Vec<i64>> {
+    run_with_system_id(input, THERMAL_RADIATOR)
+}
+
+/// Runs the diagnostic program for `system_id`, returning the diagnostic code it outputs last.
+/// Every output before it is the result of a test, and must be 0.
+pub fn diagnostic_code(input: &str, system_id: i64) -> Result<i64> {
+    let output = run_with_system_id(input, system_id)?;
+    let (&code, tests) = output
+        .split_last()
+        .context("Program did not output a diagnostic code")?;
+
+    if let Some((i, result)) = tests.iter().enumerate().find(|(_, &result)| result != 0) {
+        bail!("Diagnostic test {} failed with `{}`", i, result);
+    }
+
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_1_sample() {
+        // Outputs whatever it gets as input.
+        assert_eq!(part_1("3,0,4,0,99").unwrap(), vec![1]);
+        // Outputs 1 if the input is equal to 8, 0 otherwise.
+        assert_eq!(part_1("3,9,8,9,10,9,4,9,99,-1,8").unwrap(), vec![0]);
+    }
+
+    #[test]
+    fn test_run_with_system_id() {
+        // Outputs 999 if the input is below 8, 1000 if it is equal to 8, or 1001 if it is greater.
+        let program = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,\
+                       1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,\
+                       999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+
+        assert_eq!(run_with_system_id(program, 1).unwrap(), vec![999]);
+        assert_eq!(run_with_system_id(program, 5).unwrap(), vec![999]);
+        assert_eq!(run_with_system_id(program, 8).unwrap(), vec![1000]);
+        assert_eq!(run_with_system_id(program, 9).unwrap(), vec![1001]);
+        assert_eq!(part_2(program).unwrap(), vec![999]);
+    }
+
+    #[test]
+    fn test_diagnostic_code() {
+        assert_eq!(diagnostic_code("104,0,104,0,104,42,99", 1).unwrap(), 42);
+        assert_eq!(diagnostic_code("3,0,4,0,99", 7).unwrap(), 7);
+
+        let err = diagnostic_code("104,0,104,3,104,42,99", 1).unwrap_err();
+        assert_eq!(err.to_string(), "Diagnostic test 1 failed with `3`");
 
-    Ok(format!("{:?}", computer.into_output()))
+        assert!(diagnostic_code("99", 1).is_err());
+    }
 }
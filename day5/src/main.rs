@@ -1,16 +1,12 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
+use aoc_io::read_stdin;
 use day5::{part_1, part_2};
-use std::io::{stdin, Read};
 
 fn main() -> Result<()> {
-    let mut input = String::new();
+    let input = read_stdin()?;
 
-    stdin()
-        .read_to_string(&mut input)
-        .context("Failed to read input from stdin")?;
-
-    println!("Part 1 - Output {}", part_1(&input)?);
-    println!("Part 2 - Output {}", part_2(&input)?);
+    println!("Part 1 - Output {:?}", part_1(&input)?);
+    println!("Part 2 - Output {:?}", part_2(&input)?);
 
     Ok(())
 }
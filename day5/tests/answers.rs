@@ -0,0 +1,21 @@
+use day5::{diagnostic_code, part_1, part_2};
+
+#[test]
+fn test_puzzle_answers() {
+    let input = match aoc_io::try_read_input(5) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 5 is missing");
+            return;
+        }
+    };
+
+    assert_eq!(
+        part_1(&input).unwrap(),
+        vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 11193703]
+    );
+    assert_eq!(part_2(&input).unwrap(), vec![12410607]);
+
+    assert_eq!(diagnostic_code(&input, 1).unwrap(), 11193703);
+    assert_eq!(diagnostic_code(&input, 5).unwrap(), 12410607);
+}
@@ -1,98 +1,129 @@
-use anyhow::{bail, Result};
-use log::debug;
-use std::collections::{HashMap, HashSet, VecDeque};
+use anyhow::{bail, Context, Result};
+use aoc_graph::Graph;
+use std::collections::{HashMap, HashSet};
 
-type Vertex = String;
 const CENTER_OF_MASS: &str = "COM";
 
-#[derive(Debug)]
-struct Graph {
-    adjacency_list: HashMap<Vertex, Vec<Vertex>>,
+/// Parses `A)B` lines into `(A, B)` pairs, meaning B orbits A.
+fn parse_orbits(input: &str) -> Result<Vec<(&str, &str)>> {
+    input
+        .lines()
+        .map(|line| {
+            let edge: Vec<&str> = line.trim().split(")").collect();
+            if edge.len() != 2 {
+                bail!(
+                    "Expected edge definition to be of pattern `A)B`, found `{}`",
+                    line
+                );
+            }
+
+            Ok((edge[0], edge[1]))
+        })
+        .collect()
 }
 
-/// Graph of orbiting planets.
-impl Graph {
-    pub fn new() -> Self {
-        Graph {
-            adjacency_list: Default::default(),
-        }
+/// Maps every object to the object it orbits, every object orbits exactly one other object.
+fn parse_parents(input: &str) -> Result<HashMap<&str, &str>> {
+    Ok(parse_orbits(input)?
+        .into_iter()
+        .map(|(center, satellite)| (satellite, center))
+        .collect())
+}
+
+/// Every object `start` orbits (directly or indirectly), closest first.
+fn ancestors<'a>(parents: &HashMap<&'a str, &'a str>, start: &str) -> Vec<&'a str> {
+    let mut path = vec![];
+    let mut current = start;
+    while let Some(&parent) = parents.get(current) {
+        path.push(parent);
+        current = parent;
     }
+    path
+}
+
+/// Returns the closest object that both `a` and `b` (directly or indirectly) orbit.
+pub fn common_ancestor(input: &str, a: &str, b: &str) -> Result<String> {
+    let parents = parse_parents(input)?;
+    let ancestors_of_a: HashSet<&str> = ancestors(&parents, a).into_iter().collect();
+
+    ancestors(&parents, b)
+        .into_iter()
+        .find(|ancestor| ancestors_of_a.contains(ancestor))
+        .map(str::to_owned)
+        .with_context(|| format!("`{}` and `{}` do not have a common ancestor", a, b))
+}
 
-    pub fn with_capacity(n: usize) -> Self {
-        Graph {
-            adjacency_list: HashMap::with_capacity(n),
+/// The objects traversed when transferring from the object YOU orbit to the object SAN orbits,
+/// both ends included. The number of transfers is one less than the length of the path.
+pub fn transfer_path(input: &str) -> Result<Vec<String>> {
+    let parents = parse_parents(input)?;
+    let meeting_point = common_ancestor(input, "YOU", "SAN")?;
+
+    let mut path: Vec<String> = vec![];
+    for object in ancestors(&parents, "YOU") {
+        path.push(object.to_owned());
+        if object == meeting_point {
+            break;
         }
     }
 
-    pub fn add_edge(&mut self, from: Vertex, to: Vertex) {
-        self.adjacency_list.entry(from).or_insert(vec![]).push(to);
-    }
+    let from_san: Vec<&str> = ancestors(&parents, "SAN")
+        .into_iter()
+        .take_while(|&object| object != meeting_point)
+        .collect();
 
-    /// Returns a map of paths from vertex `start` to each other vertex in the graph.
-    /// The key is the shortest path length.
-    pub fn bfs<'a>(&'a self, start: &'a str) -> HashMap<u32, HashSet<&'a str>> {
-        let mut queue = VecDeque::new();
-        queue.push_back(start);
-
-        // Although the graph in this question should not contain loops, better safe than sorry.
-        let mut visited = HashSet::with_capacity(self.adjacency_list.len());
-        visited.insert(start);
-
-        // Extra bookeeping to allow ourselves to keep track of depth
-        // while getting away with using a queue for BFS (instead of "list of lists")
-        let mut node_to_depth = HashMap::new();
-        node_to_depth.insert(start, 0);
-
-        let mut layers = HashMap::new();
-        let mut h: HashSet<&str> = HashSet::new();
-        h.insert(start);
-        layers.insert(0, h);
-
-        while !queue.is_empty() {
-            debug!("{:?}", &queue);
-            let v = queue.pop_front().expect("Queue is not empty");
-
-            if let Some(neighbors) = self.adjacency_list.get(v) {
-                for neighbor in neighbors.iter() {
-                    if !visited.contains(neighbor.as_str()) {
-                        let parent_depth = node_to_depth.get(v).expect("parent must exist").clone();
-                        let this_depth = parent_depth + 1;
-                        node_to_depth.insert(&neighbor, this_depth);
-                        layers
-                            .entry(this_depth)
-                            .or_insert_with(HashSet::new)
-                            .insert(neighbor);
-
-                        visited.insert(neighbor.as_str());
-                        queue.push_back(neighbor.as_str())
-                    }
-                }
-            }
-        }
+    path.extend(from_san.into_iter().rev().map(str::to_owned));
 
-        layers
-    }
+    Ok(path)
 }
 
-pub fn part_1(input: &str) -> Result<u32> {
-    let mut g = Graph::new();
+/// Makes sure the orbits form a tree rooted at `COM`, given the objects a BFS from `COM` reached.
+fn validate_orbits(orbits: &[(&str, &str)], reached: &HashSet<&str>) -> Result<()> {
+    let mut parents: HashMap<&str, &str> = HashMap::with_capacity(orbits.len());
 
-    for line in input.lines() {
-        let edge: Vec<&str> = line.trim().split(")").collect();
-        if edge.len() != 2 {
+    for &(center, satellite) in orbits {
+        if satellite == CENTER_OF_MASS {
+            bail!("`{}` orbits `{}`, forming a cycle", CENTER_OF_MASS, center);
+        }
+
+        if let Some(other) = parents.insert(satellite, center) {
             bail!(
-                "Expected edge definition to be of pattern `A)B`, found `{}`",
-                line
+                "`{}` orbits both `{}` and `{}`, but can only orbit a single object",
+                satellite,
+                other,
+                center
             );
         }
+    }
+
+    // Sorted, so the same object is always reported.
+    let mut objects: Vec<&str> = orbits.iter().flat_map(|&(c, s)| vec![c, s]).collect();
+    objects.sort_unstable();
+
+    for object in objects {
+        if !reached.contains(object) {
+            bail!("`{}` is not reachable from `{}`", object, CENTER_OF_MASS);
+        }
+    }
+
+    Ok(())
+}
 
+pub fn part_1(input: &str) -> Result<u32> {
+    let orbits = parse_orbits(input)?;
+    let mut g: Graph<String> = Graph::new();
+
+    for &(center, satellite) in orbits.iter() {
         // B orbits A translates to
         // B --> A
         // We invert the edges to be able to iterate them from `COM`.
-        g.add_edge(edge[0].to_owned(), edge[1].to_owned());
+        g.add_edge(center.to_owned(), satellite.to_owned());
     }
 
-    let bfs = g.bfs(CENTER_OF_MASS);
+    let bfs = g.bfs(CENTER_OF_MASS.to_owned());
+    let reached = bfs.values().flatten().map(String::as_str).collect();
+    validate_orbits(&orbits, &reached)?;
+
     let mut total_orbits = 0;
 
     // we just count how many orbits are at each distance from `COM`
@@ -104,23 +135,14 @@ pub fn part_1(input: &str) -> Result<u32> {
 }
 
 pub fn part_2(input: &str) -> Result<u32> {
-    let mut g = Graph::new();
-
-    for line in input.lines() {
-        let edge: Vec<&str> = line.trim().split(")").collect();
-        if edge.len() != 2 {
-            bail!(
-                "Expected edge definition to be of pattern `A)B`, found `{}`",
-                line
-            );
-        }
+    let mut g: Graph<String> = Graph::new();
 
+    for (center, satellite) in parse_orbits(input)? {
         // Orbital transfers don't care about direction
-        g.add_edge(edge[0].to_owned(), edge[1].to_owned());
-        g.add_edge(edge[1].to_owned(), edge[0].to_owned());
+        g.add_undirected_edge(center.to_owned(), satellite.to_owned());
     }
 
-    let bfs = g.bfs("YOU");
+    let bfs = g.bfs("YOU".to_owned());
 
     for (len, vertexes) in bfs {
         if vertexes.contains("SAN") {
@@ -151,6 +173,23 @@ K)L";
         assert_eq!(part_1(input).unwrap(), 42);
     }
 
+    #[test]
+    pub fn test_unreachable_object() {
+        let err = part_1("COM)A\nA)B\nX)Y").unwrap_err();
+        assert_eq!(err.to_string(), "`X` is not reachable from `COM`");
+    }
+
+    #[test]
+    pub fn test_cycle() {
+        let err = part_1("COM)A\nA)B\nB)A").unwrap_err();
+        assert!(err.to_string().contains("`A`"), "{}", err);
+        assert!(err.to_string().contains("`B`"), "{}", err);
+
+        // Not connected to `COM` at all.
+        let err = part_1("COM)A\nB)C\nC)B").unwrap_err();
+        assert_eq!(err.to_string(), "`B` is not reachable from `COM`");
+    }
+
     #[test]
     pub fn test_part2() {
         let input = "\
@@ -168,5 +207,8 @@ K)L
 K)YOU
 I)SAN";
         assert_eq!(part_2(input).unwrap(), 4);
+        assert_eq!(common_ancestor(input, "YOU", "SAN").unwrap(), "D");
+        assert!(common_ancestor(input, "YOU", "NOPE").is_err());
+        assert_eq!(transfer_path(input).unwrap(), vec!["K", "J", "E", "D", "I"]);
     }
 }
@@ -1,17 +1,13 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
+use aoc_io::read_stdin;
 use day6::{part_1, part_2};
 use env_logger;
-use std::io::{stdin, Read};
 use std::time::Instant;
 
 fn main() -> Result<()> {
     env_logger::init();
 
-    let mut input = String::new();
-
-    stdin()
-        .read_to_string(&mut input)
-        .context("Failed to read input from stdin")?;
+    let input = read_stdin()?;
 
     let now = Instant::now();
     println!("Part 1 - {} in {:?}", part_1(&input)?, now.elapsed());
@@ -0,0 +1,16 @@
+use day6::{part_1, part_2, transfer_path};
+
+#[test]
+fn test_puzzle_answers() {
+    let input = match aoc_io::try_read_input(6) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 6 is missing");
+            return;
+        }
+    };
+
+    assert_eq!(part_1(&input).unwrap(), 162439);
+    assert_eq!(part_2(&input).unwrap(), 367);
+    assert_eq!(transfer_path(&input).unwrap().len() - 1, 367);
+}
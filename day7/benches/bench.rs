@@ -1,8 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use std::fs;
 
 fn benchmark(c: &mut Criterion) {
-    let s = fs::read_to_string(r#"input/opcodes"#).unwrap();
+    let s = aoc_io::read_input(7).unwrap();
 
     c.bench_function("2019 day 7 part one", |b| {
         b.iter(|| day7::part_1(&s));
@@ -34,7 +34,7 @@ fn calculate_thruster_signal(program: Vec<i64>, inputs: Vec<i64>) -> Result<i64>
     for (i, amp) in amps.iter_mut().enumerate() {
         let input = vec![inputs[i], last_input];
         debug!("Amplifier {} - {:?}", i, &input);
-        amp.write_to_input(input)?;
+        amp.write_to_input(&input)?;
         amp.run_until_halt()?;
 
         last_input = amp.read_from_output()?;
@@ -55,7 +55,7 @@ fn calculate_thruster_with_feedback_loop(program: Vec<i64>, inputs: Vec<i64>) ->
 
     for (i, amp) in amps.iter_mut().enumerate() {
         debug!("Amplifier {} - Input is {:?}", i, vec![inputs[i]]);
-        amp.write_to_input(vec![inputs[i]])?;
+        amp.write_to_input([inputs[i]])?;
     }
 
     debug!("---------- START ------------------");
@@ -63,24 +63,29 @@ fn calculate_thruster_with_feedback_loop(program: Vec<i64>, inputs: Vec<i64>) ->
     'outer: loop {
         for (amp, i) in amps.iter_mut().zip(5..=9) {
             debug!("Amplifier {} - Input is {:?}", i, last_input);
-            amp.write_to_input(vec![last_input])?;
+            amp.write_to_input([last_input])?;
 
-            'inner: loop {
+            let halted = 'inner: loop {
                 match amp.step()? {
                     ExecutionStatus::NeedInput => {
                         debug!("Amplifier {} needs input", i);
-                        break 'inner;
+                        break 'inner false;
                     }
                     ExecutionStatus::Halted => {
                         debug!("Amplifier {} is done", i);
-                        break 'outer;
+                        break 'inner true;
                     }
                     _ => {}
                 }
-            }
+            };
             last_input = amp.read_from_output()?;
 
             debug!("Output from Amplifier {} is {:?}", i, last_input);
+
+            // The signal to the thrusters is the last output of the last amplifier.
+            if halted && i == 9 {
+                break 'outer;
+            }
         }
     }
 
@@ -119,4 +124,19 @@ mod tests {
             139629729
         )
     }
+
+    #[test]
+    fn test_thruster_signal_with_feedback_larger_example() {
+        assert_eq!(
+            calculate_thruster_with_feedback_loop(
+                IntcodeComputer::parse_program(
+                    "3,52,1001,52,-5,52,3,53,1,52,56,54,1007,54,5,55,1005,55,26,1001,54,-5,54,1105,1,12,1,53,54,53,1008,54,0,55,1001,55,1,55,2,53,55,53,4,53,1001,56,-1,56,1005,56,6,99,0,0,0,0,10"
+                )
+                .unwrap(),
+                vec![9, 7, 8, 5, 6]
+            )
+            .unwrap(),
+            18216
+        )
+    }
 }
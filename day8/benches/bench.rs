@@ -1,8 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use std::fs;
 
 fn benchmark(c: &mut Criterion) {
-    let s = fs::read_to_string(r#"input\image"#).unwrap();
+    let s = aoc_io::read_input(8).unwrap();
 
     c.bench_function("2019 day 8 part one", |b| {
         b.iter(|| day8::part_1(&s));
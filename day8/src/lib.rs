@@ -6,7 +6,7 @@ use std::fmt::Write;
 type PixelRow = Vec<Pixel>;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd)]
-enum Pixel {
+pub enum Pixel {
     Black,
     White,
     Transparent,
@@ -26,6 +26,35 @@ impl fmt::Display for Pixel {
     }
 }
 
+/// Characters used to draw each kind of pixel.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Palette {
+    pub white: char,
+    pub black: char,
+    pub transparent: char,
+}
+
+impl Default for Palette {
+    /// Same glyphs as the `Display` implementation.
+    fn default() -> Self {
+        Palette {
+            white: '0',
+            black: ' ',
+            transparent: ' ',
+        }
+    }
+}
+
+impl Palette {
+    pub fn glyph(&self, pixel: Pixel) -> char {
+        match pixel {
+            Pixel::White => self.white,
+            Pixel::Black => self.black,
+            Pixel::Transparent => self.transparent,
+        }
+    }
+}
+
 impl TryFrom<u8> for Pixel {
     type Error = Error;
 
@@ -40,10 +69,10 @@ impl TryFrom<u8> for Pixel {
 }
 
 #[derive(Debug)]
-struct Layer(Vec<PixelRow>);
+pub struct Layer(Vec<PixelRow>);
 
 #[derive(Debug)]
-struct EncodedImage {
+pub struct EncodedImage {
     layers: Vec<Layer>,
     x: usize,
     y: usize,
@@ -52,6 +81,10 @@ struct EncodedImage {
 impl EncodedImage {
     /// X, Y are the dimensions of each layer in the image.
     pub fn with_dimensions(x: usize, y: usize, pixels: Vec<Pixel>) -> Result<EncodedImage> {
+        if x == 0 || y == 0 {
+            bail!("Invalid input dimensions {}x{}", x, y);
+        }
+
         let mut rows_iter = pixels.chunks_exact(x);
         let rows: Vec<PixelRow> = rows_iter.by_ref().map(|c| c.to_vec()).collect();
 
@@ -72,6 +105,16 @@ impl EncodedImage {
         Ok(EncodedImage { layers, x, y })
     }
 
+    /// Interprets each byte as a pixel value (0, 1 or 2), for inputs stored compactly as raw bytes.
+    pub fn from_bytes(bytes: &[u8], w: usize, h: usize) -> Result<EncodedImage> {
+        let pixels = bytes
+            .iter()
+            .map(|&b| Pixel::try_from(b))
+            .collect::<Result<Vec<Pixel>>>()?;
+
+        EncodedImage::with_dimensions(w, h, pixels)
+    }
+
     pub fn checksum(&self) -> Result<usize> {
         let l = self
             .layers
@@ -126,6 +169,41 @@ impl Layer {
         let mut i = self.0.iter().cloned();
         std::iter::from_fn(move || i.next())
     }
+
+    /// Like `Display`, but with the glyphs from `palette`.
+    pub fn render_with(&self, palette: &Palette) -> String {
+        let mut rendered =
+            String::with_capacity(self.0.len() * (self.0.first().map_or(0, Vec::len) + 1));
+
+        for row in self.0.iter() {
+            rendered.extend(row.iter().map(|&pixel| palette.glyph(pixel)));
+            rendered.push('\n');
+        }
+
+        rendered
+    }
+
+    /// Renders the layer as a plain (P1) netpbm bitmap, which most image viewers can open.
+    /// White pixels are drawn as ink (`1`), black and transparent ones are left blank (`0`).
+    pub fn to_pbm(&self) -> String {
+        let width = self.0.first().map_or(0, Vec::len);
+        let mut pbm = format!("P1\n{} {}\n", width, self.0.len());
+
+        for row in self.0.iter() {
+            let bits: Vec<&str> = row
+                .iter()
+                .map(|&pixel| match pixel {
+                    Pixel::White => "1",
+                    Pixel::Black | Pixel::Transparent => "0",
+                })
+                .collect();
+
+            pbm.push_str(&bits.join(" "));
+            pbm.push('\n');
+        }
+
+        pbm
+    }
 }
 
 impl fmt::Display for Layer {
@@ -152,18 +230,28 @@ fn parse_pixels(input: &str) -> Result<Vec<Pixel>> {
 /// Find the layer that contains the fewest 0 digits.
 /// On that layer, what is the number of 1 digits multiplied by the number of 2 digits?
 pub fn part_1(input: &str) -> Result<usize> {
+    part_1_with_dims(input, 25, 6)
+}
+
+/// Same as `part_1`, for an image with `x` by `y` layers.
+pub fn part_1_with_dims(input: &str, x: usize, y: usize) -> Result<usize> {
     let input = parse_pixels(input)?;
 
-    let im = EncodedImage::with_dimensions(25, 6, input)?;
+    let im = EncodedImage::with_dimensions(x, y, input)?;
 
     im.checksum().context("Failed to calculate checksum")
 }
 
 pub fn part_2(input: &str) -> Result<String> {
+    part_2_with_dims(input, 25, 6)
+}
+
+/// Same as `part_2`, for an image with `x` by `y` layers.
+pub fn part_2_with_dims(input: &str, x: usize, y: usize) -> Result<String> {
     let input = parse_pixels(input)?;
-    let im = EncodedImage::with_dimensions(25, 6, input)?;
+    let im = EncodedImage::with_dimensions(x, y, input)?;
 
-    let mut result = String::with_capacity(25 * 10);
+    let mut result = String::with_capacity((x + 1) * y + 80);
 
     writeln!(
         result,
@@ -189,4 +277,68 @@ mod tests {
         let im = EncodedImage::with_dimensions(3, 2, pixels).unwrap();
         assert_eq!(im.checksum().unwrap(), 5);
     }
+
+    #[test]
+    fn test_with_dims() {
+        assert_eq!(part_1_with_dims("121111222012", 3, 2).unwrap(), 5);
+
+        let decoded = part_2_with_dims("0222112222120000", 2, 2).unwrap();
+        assert!(decoded.contains("\n 0\n0 \n"), "{:?}", decoded);
+
+        assert!(part_2_with_dims("0222112222120000", 3, 2).is_err());
+        assert!(part_1_with_dims("1212", 0, 2).is_err());
+        assert!(part_1_with_dims("1212", 2, 0).is_err());
+    }
+
+    #[test]
+    fn test_to_pbm() {
+        let pixels = parse_pixels("0222112222120000").unwrap();
+        let pbm = EncodedImage::with_dimensions(2, 2, pixels)
+            .unwrap()
+            .decode()
+            .to_pbm();
+
+        assert_eq!(pbm, "P1\n2 2\n0 1\n1 0\n");
+
+        let pixels = parse_pixels(&"012".repeat(50)).unwrap();
+        let pbm = EncodedImage::with_dimensions(25, 6, pixels)
+            .unwrap()
+            .decode()
+            .to_pbm();
+        let mut lines = pbm.lines();
+
+        assert_eq!(lines.next(), Some("P1"));
+        assert_eq!(lines.next(), Some("25 6"));
+        assert_eq!(lines.flat_map(|l| l.split_whitespace()).count(), 25 * 6);
+    }
+
+    #[test]
+    fn test_render_with() {
+        let pixels = parse_pixels("0222112222120000").unwrap();
+        let decoded = EncodedImage::with_dimensions(2, 2, pixels)
+            .unwrap()
+            .decode();
+
+        let palette = Palette {
+            white: '#',
+            black: '.',
+            transparent: ' ',
+        };
+
+        assert_eq!(decoded.render_with(&palette), ".#\n#.\n");
+        assert_eq!(
+            decoded.render_with(&Palette::default()),
+            decoded.to_string()
+        );
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let im = EncodedImage::from_bytes(&[0, 2, 2, 2, 1, 1, 2, 2, 2, 2, 1, 2, 0, 0, 0, 0], 2, 2)
+            .unwrap();
+        assert_eq!(im.decode().to_string(), " 0\n0 \n");
+
+        assert!(EncodedImage::from_bytes(&[0, 1, 3, 0], 2, 2).is_err());
+        assert!(EncodedImage::from_bytes(&[0, 1, 2], 2, 2).is_err());
+    }
 }
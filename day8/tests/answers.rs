@@ -0,0 +1,14 @@
+use day8::part_1;
+
+#[test]
+fn test_puzzle_answers() {
+    let input = match aoc_io::try_read_input(8) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 8 is missing");
+            return;
+        }
+    };
+
+    assert_eq!(part_1(&input).unwrap(), 2806);
+}
@@ -1,29 +1,88 @@
 #![deny(unused_must_use)]
-use anyhow::{Context, Result};
+use anyhow::{bail, Result};
 use intcode_computer::IntcodeComputer;
 
-pub fn part_1(input: &str) -> Result<i64> {
+/// Input which makes BOOST check every opcode, then output its keycode.
+const TEST_MODE: i64 = 1;
+
+fn run(input: &str, mode: i64) -> Result<Vec<i64>> {
     let program = IntcodeComputer::parse_program(input)?;
     let mut computer = IntcodeComputer::new(program);
 
-    computer.write_to_input(vec![1])?;
+    computer.write_to_input([mode])?;
     computer.run_until_halt()?;
 
-    computer
-        .into_output()
-        .pop_front()
-        .context("Expected output")
+    Ok(computer.into_output().into_iter().collect())
+}
+
+/// Runs the BOOST program in `mode`, returning its single output.
+/// More than one output means the program found malfunctioning opcodes.
+pub fn run_boost(input: &str, mode: i64) -> Result<i64> {
+    let output = run(input, mode)?;
+    match output.as_slice() {
+        &[value] => Ok(value),
+        [] => bail!("Expected output"),
+        _ => bail!(
+            "Expected a single output, got {:?} - some opcodes are malfunctioning",
+            output
+        ),
+    }
+}
+
+/// Runs BOOST in test mode, which outputs the malfunctioning opcodes before the keycode.
+pub fn self_test(input: &str) -> Result<()> {
+    match run(input, TEST_MODE)?.split_last() {
+        Some((_, [])) => Ok(()),
+        Some((_, malfunctioning)) => bail!(
+            "BOOST self test failed, malfunctioning opcodes: {:?}",
+            malfunctioning
+        ),
+        None => bail!("BOOST self test did not output a keycode"),
+    }
+}
+
+pub fn part_1(input: &str) -> Result<i64> {
+    run_boost(input, TEST_MODE)
 }
 
 pub fn part_2(input: &str) -> Result<i64> {
-    let program = IntcodeComputer::parse_program(input)?;
-    let mut computer = IntcodeComputer::new(program);
+    run_boost(input, 2)
+}
 
-    computer.write_to_input(vec![2])?;
-    computer.run_until_halt()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_boost() {
+        // Moves the relative base to 5, then outputs the value 2 cells after it.
+        assert_eq!(run_boost("109,5,204,2,99,0,0,42", 1).unwrap(), 42);
+        // Outputs the input.
+        assert_eq!(run_boost("203,0,204,0,99", 2).unwrap(), 2);
+
+        // Outputs a copy of itself.
+        let quine = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        let err = run_boost(quine, 1).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Expected a single output, got [109, 1, 204"),
+            "{}",
+            err
+        );
+
+        assert!(run_boost("99", 1).is_err());
+    }
+
+    #[test]
+    fn test_self_test() {
+        self_test("3,0,104,1234,99").unwrap();
+
+        let err = self_test("3,0,104,203,104,9,104,1234,99").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "BOOST self test failed, malfunctioning opcodes: [203, 9]"
+        );
 
-    computer
-        .into_output()
-        .pop_front()
-        .context("Expected output")
+        assert!(self_test("3,0,99").is_err());
+    }
 }
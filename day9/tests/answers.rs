@@ -0,0 +1,16 @@
+use day9::{part_1, part_2, self_test};
+
+#[test]
+fn test_puzzle_answers() {
+    let input = match aoc_io::try_read_input(9) {
+        Some(input) => input,
+        None => {
+            eprintln!("Skipping, the input of day 9 is missing");
+            return;
+        }
+    };
+
+    self_test(&input).unwrap();
+    assert_eq!(part_1(&input).unwrap(), 3601950151);
+    assert_eq!(part_2(&input).unwrap(), 64236);
+}
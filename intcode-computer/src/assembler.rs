@@ -0,0 +1,355 @@
+use crate::{Address, Memory};
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+
+/// Mnemonic, opcode, and for each parameter whether the instruction writes to it.
+const INSTRUCTIONS: [(&str, i64, &[bool]); 10] = [
+    ("ADD", 1, &[false, false, true]),
+    ("MUL", 2, &[false, false, true]),
+    ("IN", 3, &[true]),
+    ("OUT", 4, &[false]),
+    ("JNZ", 5, &[false, false]),
+    ("JZ", 6, &[false, false]),
+    ("LT", 7, &[false, false, true]),
+    ("EQ", 8, &[false, false, true]),
+    ("ARB", 9, &[false]),
+    ("HALT", 99, &[]),
+];
+
+enum Statement<'a> {
+    Instruction {
+        opcode: i64,
+        writes: &'static [bool],
+        operands: Vec<&'a str>,
+    },
+    /// Raw values, copied to the program as is.
+    Data(Vec<&'a str>),
+}
+
+impl<'a> Statement<'a> {
+    fn len(&self) -> usize {
+        match self {
+            Statement::Instruction { operands, .. } => operands.len() + 1,
+            Statement::Data(values) => values.len(),
+        }
+    }
+}
+
+fn is_label(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Splits a line into its label (if any) and statement (if any).
+fn parse_line(line: &str) -> Result<(Option<&str>, Option<Statement<'_>>)> {
+    let line = line.split(';').next().unwrap_or_default().trim();
+
+    let (label, rest) = match line.find(':') {
+        Some(i) => {
+            let label = line[..i].trim();
+            if !is_label(label) {
+                bail!("Invalid label name `{}`", label);
+            }
+            (Some(label), line[i + 1..].trim())
+        }
+        None => (None, line),
+    };
+
+    let mut tokens = rest.split_whitespace();
+    let mnemonic = match tokens.next() {
+        Some(mnemonic) => mnemonic,
+        None => return Ok((label, None)),
+    };
+    let operands: Vec<&str> = tokens.collect();
+
+    if mnemonic.eq_ignore_ascii_case("DATA") {
+        if operands.is_empty() {
+            bail!("`DATA` requires at least one value");
+        }
+        return Ok((label, Some(Statement::Data(operands))));
+    }
+
+    let &(name, opcode, writes) = INSTRUCTIONS
+        .iter()
+        .find(|(name, _, _)| name.eq_ignore_ascii_case(mnemonic))
+        .with_context(|| format!("Unknown mnemonic `{}`", mnemonic))?;
+
+    if operands.len() != writes.len() {
+        bail!(
+            "`{}` takes {} operands, found {}",
+            name,
+            writes.len(),
+            operands.len()
+        );
+    }
+
+    Ok((
+        label,
+        Some(Statement::Instruction {
+            opcode,
+            writes,
+            operands,
+        }),
+    ))
+}
+
+/// A number, or the address of a label.
+fn resolve(value: &str, labels: &HashMap<&str, usize>) -> Result<i64> {
+    if let Ok(number) = value.parse::<i64>() {
+        return Ok(number);
+    }
+
+    labels
+        .get(value)
+        .map(|&address| address as i64)
+        .with_context(|| format!("Unknown label `{}`", value))
+}
+
+/// Parses `@x` (position), `#x` (immediate) or `%x` (relative) into its mode and value.
+fn parse_operand(
+    operand: &str,
+    is_write: bool,
+    labels: &HashMap<&str, usize>,
+) -> Result<(i64, i64)> {
+    let mode = match operand.chars().next() {
+        Some('@') => 0,
+        Some('#') if is_write => bail!("Cannot write to immediate operand `{}`", operand),
+        Some('#') => 1,
+        Some('%') => 2,
+        _ => bail!(
+            "Operand `{}` must start with `@` (position), `#` (immediate) or `%` (relative)",
+            operand
+        ),
+    };
+
+    Ok((mode, resolve(&operand[1..], labels)?))
+}
+
+/// Compiles assembly source into an Intcode program.
+///
+/// Each line holds an optional `label:` followed by an optional statement, `;` starts a comment.
+/// A statement is either an instruction (`ADD`, `MUL`, `IN`, `OUT`, `JNZ`, `JZ`, `LT`, `EQ`,
+/// `ARB` or `HALT`) followed by its operands, or `DATA` followed by raw values.
+/// Operands are prefixed with `@` for position mode, `#` for immediate mode or `%` for relative mode,
+/// and can be either numbers or labels, which resolve to the address they were defined at.
+pub fn assemble(src: &str) -> Result<Memory> {
+    let mut statements = vec![];
+    let mut labels = HashMap::new();
+    let mut address = 0;
+
+    for (i, line) in src.lines().enumerate() {
+        let (label, statement) =
+            parse_line(line).with_context(|| format!("Line {}: `{}`", i + 1, line.trim()))?;
+
+        if let Some(label) = label {
+            if labels.insert(label, address).is_some() {
+                bail!(
+                    "Line {}: label `{}` is defined more than once",
+                    i + 1,
+                    label
+                );
+            }
+        }
+
+        if let Some(statement) = statement {
+            address += statement.len();
+            statements.push((i, line, statement));
+        }
+    }
+
+    let mut program = Vec::with_capacity(address);
+
+    for (i, line, statement) in statements {
+        let context = || format!("Line {}: `{}`", i + 1, line.trim());
+
+        match statement {
+            Statement::Instruction {
+                opcode,
+                writes,
+                operands,
+            } => {
+                let mut instruction = opcode;
+                let mut values = Vec::with_capacity(operands.len());
+
+                for (n, (operand, &is_write)) in operands.iter().zip(writes).enumerate() {
+                    let (mode, value) =
+                        parse_operand(operand, is_write, &labels).with_context(context)?;
+                    instruction += mode * 10_i64.pow(n as u32 + 2);
+                    values.push(value);
+                }
+
+                program.push(instruction);
+                program.extend(values);
+            }
+            Statement::Data(values) => {
+                for value in values {
+                    program.push(resolve(value, &labels).with_context(context)?);
+                }
+            }
+        }
+    }
+
+    Ok(program)
+}
+
+/// Decodes a raw instruction into its mnemonic and the mode of each of its parameters,
+/// along with whether the parameter is written to.
+/// Returns `None` if the computer would fail to decode it.
+pub(crate) fn decode(raw: i64) -> Option<(&'static str, Vec<(i64, bool)>)> {
+    if raw < 0 {
+        return None;
+    }
+
+    let &(name, _, writes) = INSTRUCTIONS
+        .iter()
+        .find(|(_, opcode, _)| *opcode == raw % 100)?;
+
+    // Halt has no parameters, so like the computer we ignore any mode digits.
+    if writes.is_empty() {
+        return Some((name, vec![]));
+    }
+
+    let mut modes = raw / 100;
+    let mut parameters = Vec::with_capacity(writes.len());
+
+    for &is_write in writes {
+        if modes % 10 > 2 {
+            return None;
+        }
+        parameters.push((modes % 10, is_write));
+        modes /= 10;
+    }
+
+    if modes != 0 {
+        return None;
+    }
+
+    Some((name, parameters))
+}
+
+/// Decodes the instruction at `address` back to assembly, using `read` to access memory.
+/// Returns the instruction along with the address of the next one.
+/// Values which aren't valid instructions are shown as `DATA`.
+pub fn disassemble(
+    read: impl Fn(Address) -> Result<i64>,
+    address: Address,
+) -> Result<(String, Address)> {
+    let raw = read(address)?;
+    let data = || Ok((format!("DATA {}", raw), address + 1));
+
+    let (name, parameters) = match decode(raw) {
+        Some(decoded) => decoded,
+        None => return data(),
+    };
+
+    let mut text = name.to_owned();
+
+    for (n, &parameter) in parameters.iter().enumerate() {
+        let prefix = match parameter {
+            (0, _) => '@',
+            (1, false) => '#',
+            (2, _) => '%',
+            // The computer treats immediate mode as position mode when writing,
+            // but that can't be assembled back.
+            _ => return data(),
+        };
+
+        text.push(' ');
+        text.push(prefix);
+        text.push_str(&read(address + n as Address + 1)?.to_string());
+    }
+
+    Ok((text, address + parameters.len() as Address + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntcodeComputer;
+
+    fn run(src: &str, input: &[i64]) -> Vec<i64> {
+        let mut computer = IntcodeComputer::new(assemble(src).unwrap());
+        computer.write_to_input(input).unwrap();
+        computer.run_until_halt().unwrap();
+        computer.into_output().into_iter().collect()
+    }
+
+    #[test]
+    fn test_assemble() {
+        let src = "\
+            ADD #20 #22 @result
+            OUT @result
+            HALT
+            result: DATA 0";
+
+        assert_eq!(assemble(src).unwrap(), vec![1101, 20, 22, 7, 4, 7, 99, 0]);
+        assert_eq!(run(src, &[]), vec![42]);
+    }
+
+    #[test]
+    fn test_assemble_loop() {
+        // Counts down from the input to 1.
+        let src = "
+            IN @counter
+        loop:
+            OUT @counter
+            ADD @counter #-1 @counter  ; decrement
+            JNZ @counter #loop
+            HALT
+
+        counter: DATA 0
+        ";
+
+        assert_eq!(run(src, &[3]), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let src = "IN @9\nMUL @9 #-3 %2\nJZ #0 #0\nHALT";
+        let program = assemble(src).unwrap();
+        let read = |i: Address| Ok(program[i as usize]);
+
+        let mut address = 0;
+        let mut lines = vec![];
+        while (address as usize) < program.len() {
+            let (line, next) = disassemble(read, address).unwrap();
+            lines.push(line);
+            address = next;
+        }
+
+        assert_eq!(lines.join("\n"), src);
+        assert_eq!(
+            disassemble(|_| Ok(42), 0).unwrap(),
+            ("DATA 42".to_owned(), 1)
+        );
+        assert_eq!(
+            disassemble(|_| Ok(11101), 0).unwrap(),
+            ("DATA 11101".to_owned(), 1)
+        );
+        assert_eq!(
+            disassemble(|_| Ok(-1), 0).unwrap(),
+            ("DATA -1".to_owned(), 1)
+        );
+    }
+
+    #[test]
+    fn test_decode_halt_with_modes() {
+        for &raw in &[99, 199, 10099] {
+            assert_eq!(decode(raw), Some(("HALT", vec![])));
+        }
+        assert_eq!(decode(-99), None);
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        let err = |src: &str| format!("{:#}", assemble(src).unwrap_err());
+
+        assert!(err("NOP").contains("Unknown mnemonic `NOP`"));
+        assert!(err("HALT\nOUT @nowhere").contains("Line 2"));
+        assert!(err("OUT @nowhere").contains("Unknown label `nowhere`"));
+        assert!(err("ADD #1 #2").contains("takes 3 operands, found 2"));
+        assert!(err("ADD #1 #2 #3").contains("Cannot write to immediate operand `#3`"));
+        assert!(err("OUT 5").contains("must start with"));
+        assert!(err("a: HALT\na: HALT").contains("more than once"));
+    }
+}
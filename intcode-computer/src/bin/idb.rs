@@ -0,0 +1,224 @@
+//! An interactive Intcode debugger.
+//!
+//! Usage: `idb <program>`, then type `help` for the list of commands.
+use anyhow::{bail, Context, Result};
+use intcode_computer::{Address, ExecutionStatus, IntcodeComputer};
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::io::{stdin, stdout, BufRead, Write};
+
+const HELP: &str = "\
+step [n]           execute the next `n` instructions (defaults to 1)
+run                run until the program halts, needs input or hits a breakpoint
+break <addr>       toggle a breakpoint at `addr`
+mem <addr> [n]     show `n` memory cells starting at `addr` (defaults to 1)
+regs               show the instruction pointer and relative base
+disasm [addr] [n]  disassemble `n` instructions starting at `addr` (defaults to eip and 5)
+input <n>...       queue input values
+output             show (and consume) the program's output
+quit               exit the debugger";
+
+/// Most memory cells `mem` shows at once, so a typo can't flood the terminal.
+const MAX_MEM_CELLS: i64 = 1024;
+
+struct Debugger {
+    computer: IntcodeComputer,
+    breakpoints: BTreeSet<Address>,
+    halted: bool,
+}
+
+impl Debugger {
+    fn new(program: &str) -> Result<Self> {
+        Ok(Debugger {
+            computer: IntcodeComputer::new(IntcodeComputer::parse_program(program)?),
+            breakpoints: BTreeSet::new(),
+            halted: false,
+        })
+    }
+
+    /// Executes a single instruction, describing why execution stopped if it did.
+    fn step(&mut self) -> Result<Option<String>> {
+        if self.halted {
+            return Ok(Some("Program has halted".to_owned()));
+        }
+
+        Ok(match self.computer.step()? {
+            ExecutionStatus::Done => None,
+            ExecutionStatus::NeedInput => Some(format!(
+                "Waiting for input at eip {}, use `input <n>`",
+                self.computer.eip()
+            )),
+            ExecutionStatus::Halted => {
+                self.halted = true;
+                Some("Program has halted".to_owned())
+            }
+        })
+    }
+
+    fn current_instruction(&self) -> Result<String> {
+        let eip = self.computer.eip();
+        Ok(format!("{:>6}: {}", eip, self.computer.disassemble(eip)?.0))
+    }
+
+    /// Runs a single command, returning what should be printed.
+    fn execute(&mut self, line: &str) -> Result<String> {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let args = words
+            .map(|word| {
+                word.parse::<i64>()
+                    .with_context(|| format!("Expected a number, found `{}`", word))
+            })
+            .collect::<Result<Vec<i64>>>()?;
+
+        let arg = |i: usize, default: Option<i64>| {
+            args.get(i)
+                .copied()
+                .or(default)
+                .with_context(|| format!("`{}` requires an argument, see `help`", command))
+        };
+
+        let out = match command {
+            "step" | "s" => {
+                for _ in 0..arg(0, Some(1))? {
+                    if let Some(reason) = self.step()? {
+                        return Ok(reason);
+                    }
+                }
+                self.current_instruction()?
+            }
+            "run" | "r" => loop {
+                if let Some(reason) = self.step()? {
+                    break reason;
+                }
+                if self.breakpoints.contains(&self.computer.eip()) {
+                    break format!("Breakpoint\n{}", self.current_instruction()?);
+                }
+            },
+            "break" | "b" => {
+                let address = arg(0, None)?;
+                if self.breakpoints.remove(&address) {
+                    format!("Removed breakpoint at {}", address)
+                } else {
+                    self.breakpoints.insert(address);
+                    format!("Added breakpoint at {}", address)
+                }
+            }
+            "mem" | "m" => {
+                let address = arg(0, None)?;
+                let count = arg(1, Some(1))?;
+                if count > MAX_MEM_CELLS {
+                    bail!("Can show at most {} cells at once", MAX_MEM_CELLS);
+                }
+                let end = address.checked_add(count).with_context(|| {
+                    format!("`{}` cells from {} is out of range", count, address)
+                })?;
+                (address..end)
+                    .map(|i| Ok(format!("{:>6}: {}", i, self.computer.get(i)?)))
+                    .collect::<Result<Vec<String>>>()?
+                    .join("\n")
+            }
+            "regs" => format!("eip: {}\nebp: {}", self.computer.eip(), self.computer.ebp()),
+            "disasm" | "d" => {
+                let mut address = arg(0, Some(self.computer.eip()))?;
+                let mut lines = vec![];
+                for _ in 0..arg(1, Some(5))? {
+                    let (instruction, next) = self.computer.disassemble(address)?;
+                    lines.push(format!("{:>6}: {}", address, instruction));
+                    address = next;
+                }
+                lines.join("\n")
+            }
+            "input" | "i" => {
+                if args.is_empty() {
+                    bail!("`input` requires at least one value");
+                }
+                self.computer.write_to_input(&args)?;
+                format!("Queued {:?}", args)
+            }
+            "output" | "o" => {
+                let mut outputs = vec![];
+                while let Ok(value) = self.computer.read_from_output() {
+                    outputs.push(value);
+                }
+                format!("{:?}", outputs)
+            }
+            "help" | "h" => HELP.to_owned(),
+            "" => String::new(),
+            _ => bail!("Unknown command `{}`, see `help`", command),
+        };
+
+        Ok(out)
+    }
+}
+
+fn main() -> Result<()> {
+    let path = env::args().nth(1).context("Usage: idb <program>")?;
+    let program =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read `{}`", path))?;
+    let mut debugger = Debugger::new(&program)?;
+
+    println!("{}", debugger.current_instruction()?);
+
+    let stdin = stdin();
+    loop {
+        print!("(idb) ");
+        stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break;
+        }
+
+        match line.trim() {
+            "quit" | "q" => break,
+            line => match debugger.execute(line) {
+                Ok(out) if out.is_empty() => {}
+                Ok(out) => println!("{}", out),
+                Err(e) => println!("Error: {:#}", e),
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debugger() {
+        // From day5 - outputs 1 if the input is equal to 8, 0 otherwise.
+        let mut debugger = Debugger::new("3,9,8,9,10,9,4,9,99,-1,8").unwrap();
+
+        assert_eq!(
+            debugger.execute("disasm 0 2").unwrap(),
+            "     0: IN @9\n     2: EQ @9 @10 @9"
+        );
+        assert!(debugger
+            .execute("step")
+            .unwrap()
+            .contains("Waiting for input"));
+
+        debugger.execute("input 8").unwrap();
+        assert_eq!(debugger.execute("step").unwrap(), "     2: EQ @9 @10 @9");
+        assert_eq!(debugger.execute("mem 9").unwrap(), "     9: 8");
+
+        debugger.execute("break 6").unwrap();
+        assert_eq!(
+            debugger.execute("run").unwrap(),
+            "Breakpoint\n     6: OUT @9"
+        );
+        assert_eq!(debugger.execute("regs").unwrap(), "eip: 6\nebp: 0");
+
+        assert_eq!(debugger.execute("run").unwrap(), "Program has halted");
+        assert_eq!(debugger.execute("output").unwrap(), "[1]");
+
+        assert!(debugger.execute("mem").is_err());
+        assert!(debugger.execute("mem 9 100000").is_err());
+        assert!(debugger.execute(&format!("mem {} 2", i64::MAX)).is_err());
+        assert!(debugger.execute("jump 1").is_err());
+    }
+}
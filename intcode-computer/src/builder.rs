@@ -0,0 +1,101 @@
+use crate::{IntcodeComputer, IntcodeError, Memory, MemoryModel};
+
+/// Configures an `IntcodeComputer`, see `IntcodeComputer::builder`.
+#[derive(Debug, Default)]
+pub struct Builder {
+    program: Memory,
+    memory_cells: Option<usize>,
+    instruction_limit: Option<u64>,
+    inputs: Vec<i64>,
+}
+
+impl Builder {
+    pub fn program(mut self, program: Memory) -> Self {
+        self.program = program;
+        self
+    }
+
+    /// Total number of memory cells, the program is padded with zeroes up to this size.
+    /// Defaults to the same size as `IntcodeComputer::new`.
+    pub fn memory_cells(mut self, cells: usize) -> Self {
+        self.memory_cells = Some(cells);
+        self
+    }
+
+    /// Executing more than `limit` instructions is an error, useful for programs that might never halt.
+    pub fn instruction_limit(mut self, limit: u64) -> Self {
+        self.instruction_limit = Some(limit);
+        self
+    }
+
+    /// Values to preload into the input queue.
+    pub fn inputs(mut self, inputs: &[i64]) -> Self {
+        self.inputs.extend_from_slice(inputs);
+        self
+    }
+
+    pub fn build(self) -> Result<IntcodeComputer, IntcodeError> {
+        let cells = self
+            .memory_cells
+            .unwrap_or(IntcodeComputer::DEFAULT_MEMORY_CELLS);
+        if self.program.len() > cells {
+            return Err(IntcodeError::ProgramTooLarge {
+                len: self.program.len(),
+                cells,
+            });
+        }
+
+        let mut memory = self.program;
+        memory.resize(cells, 0);
+
+        let mut computer = IntcodeComputer::with_memory_model(MemoryModel::Dense(memory));
+        computer.instruction_limit = self.instruction_limit;
+        computer.write_to_input(&self.inputs)?;
+
+        Ok(computer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder() {
+        // Adds the two inputs together.
+        let program = IntcodeComputer::parse_program("3,11,3,12,1,11,12,13,4,13,99").unwrap();
+        let mut computer = IntcodeComputer::builder()
+            .program(program.clone())
+            .memory_cells(16)
+            .inputs(&[20, 22])
+            .build()
+            .unwrap();
+
+        computer.run_until_halt().unwrap();
+        assert_eq!(computer.read_from_output().unwrap(), 42);
+        assert!(computer.get(16).is_err());
+
+        assert!(matches!(
+            IntcodeComputer::builder()
+                .program(program)
+                .memory_cells(8)
+                .build(),
+            Err(IntcodeError::ProgramTooLarge { len: 11, cells: 8 })
+        ));
+    }
+
+    #[test]
+    fn test_instruction_limit() {
+        // Jumps to itself forever.
+        let program = IntcodeComputer::parse_program("1105,1,0").unwrap();
+        let mut computer = IntcodeComputer::builder()
+            .program(program)
+            .instruction_limit(100)
+            .build()
+            .unwrap();
+
+        let err = computer.run_until_halt().unwrap_err();
+        assert_eq!(err, IntcodeError::InstructionLimit { limit: 100 });
+        assert_eq!(err.to_string(), "Instruction limit of 100 reached");
+    }
+}
@@ -0,0 +1,44 @@
+use crate::Address;
+use thiserror::Error;
+
+/// Errors returned by the computer, so callers can tell a program waiting for input apart
+/// from a broken one.
+///
+/// Only running a program returns these. Loading one (`parse_program`, `validate`, `assemble`,
+/// `load_memory` and `dump_memory`) returns `anyhow` errors, as those only get reported.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum IntcodeError {
+    /// Accessing an address outside of (dense) memory, including negative ones.
+    /// `eip` is the instruction making the access, or the current eip for direct accesses.
+    #[error("Out of bounds memory access at address `0x{addr:08x} ({addr})` at eip {eip}")]
+    OutOfBounds { addr: Address, eip: Address },
+    /// The program is paused at an input instruction, returned by `run_until_halt`.
+    #[error("Program requested input but none was available at eip {eip}")]
+    NeedInput { eip: Address },
+    /// Unknown opcode, or parameter modes which are invalid for it.
+    #[error("Invalid instruction `{raw}` at eip {eip}")]
+    InvalidOpcode { raw: i64, eip: Address },
+    /// Overflow is not defined by the spec, and is most likely a bug in the program.
+    #[error("{operation} of `{left}` and `{right}` overflows at eip {eip}")]
+    Overflow {
+        operation: &'static str,
+        left: i64,
+        right: i64,
+        eip: Address,
+    },
+    #[error("Instruction limit of {limit} reached")]
+    InstructionLimit { limit: u64 },
+    #[error("Input queue is full, cannot write `{value}`")]
+    InputFull { value: i64 },
+    #[error("Output queue is full, cannot write `{value}`")]
+    OutputFull { value: i64 },
+    /// The receiving side of a channel hung up, so the value would be lost.
+    #[error("Output receiver hung up, cannot write `{value}`")]
+    OutputClosed { value: i64 },
+    #[error("Output queue is empty")]
+    OutputEmpty,
+    #[error("No recorded history to step back through")]
+    NoHistory,
+    #[error("Program of length {len} does not fit in {cells} memory cells")]
+    ProgramTooLarge { len: usize, cells: usize },
+}
@@ -0,0 +1,101 @@
+//! Runs randomly generated (but well formed) programs, making sure the computer never panics.
+use crate::{ExecutionStatus, IntcodeComputer, Memory};
+
+const SEED: u64 = 0x2019_1202;
+const PROGRAMS: usize = 2000;
+const STEP_CAP: usize = 1000;
+
+/// Small xorshift generator, good enough for fuzzing and keeps runs reproducible.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> i64 {
+        (self.next() % n) as i64
+    }
+}
+
+/// Opcodes with the number of parameters they take.
+const OPCODES: [(i64, u32); 10] = [
+    (1, 3),
+    (2, 3),
+    (3, 1),
+    (4, 1),
+    (5, 2),
+    (6, 2),
+    (7, 3),
+    (8, 3),
+    (9, 1),
+    (99, 0),
+];
+
+fn random_program(rng: &mut XorShift) -> Memory {
+    let n_instructions = 1 + rng.below(16) as usize;
+
+    // Lay out the instructions first, so we know the final size of the program.
+    let mut layout = Vec::with_capacity(n_instructions);
+    let mut len = 0;
+    for _ in 0..n_instructions {
+        let (op, n_params) = OPCODES[rng.below(OPCODES.len() as u64) as usize];
+        layout.push((op, n_params));
+        len += 1 + n_params as i64;
+    }
+
+    let mut program = Vec::with_capacity(len as usize);
+    for (op, n_params) in layout {
+        let mut modes = 0;
+        let mut params = Vec::with_capacity(n_params as usize);
+
+        for i in 0..n_params {
+            let mode = rng.below(3);
+            modes += mode * 10_i64.pow(i);
+
+            params.push(match mode {
+                // Position - any in-bounds address.
+                0 => rng.below(len as u64),
+                // Immediate - any small value, jump targets stay in bounds.
+                1 => rng.below(len as u64),
+                // Relative - a small offset from the relative base.
+                _ => rng.below(len as u64) - len / 2,
+            });
+        }
+
+        program.push(op + modes * 100);
+        program.extend(params);
+    }
+
+    program
+}
+
+fn run_with_step_cap(mut computer: IntcodeComputer, rng: &mut XorShift) {
+    let input: Vec<i64> = (0..4).map(|_| rng.below(100)).collect();
+    computer.write_to_input(&input).unwrap();
+
+    for _ in 0..STEP_CAP {
+        match computer.step() {
+            Ok(ExecutionStatus::Done) => {}
+            Ok(ExecutionStatus::NeedInput) | Ok(ExecutionStatus::Halted) | Err(_) => return,
+        }
+    }
+}
+
+#[test]
+fn test_fuzz_never_panics() {
+    let mut rng = XorShift(SEED);
+
+    for _ in 0..PROGRAMS {
+        let program = random_program(&mut rng);
+
+        run_with_step_cap(
+            IntcodeComputer::from_program_without_extra_memory(program.clone()),
+            &mut rng,
+        );
+        run_with_step_cap(IntcodeComputer::with_sparse_memory(program), &mut rng);
+    }
+}
@@ -1,11 +1,25 @@
-use anyhow::{Error, Result};
+use crate::IntcodeError;
 use log::debug;
 use std::collections::VecDeque;
+use std::sync::mpsc::{Receiver, Sender};
 
+/// An I/O backend for the computer.
+pub trait IntcodeIo {
+    /// Returns the next input value, or `None` if no input is available.
+    fn read(&mut self) -> Option<i64>;
+    /// Handles a value outputted by the computer.
+    /// Backends which can't accept the value (a full queue, a closed channel) return an error,
+    /// which stops the computer instead of losing the output.
+    fn write(&mut self, v: i64) -> Result<(), IntcodeError>;
+}
+
+/// The default backend, input and output are kept in queues.
 #[derive(Debug)]
 pub struct Io {
     input: VecDeque<i64>,
     output: VecDeque<i64>,
+    input_cap: Option<usize>,
+    output_cap: Option<usize>,
 }
 
 impl Io {
@@ -13,6 +27,18 @@ impl Io {
         Io {
             input: VecDeque::new(),
             output: VecDeque::new(),
+            input_cap: None,
+            output_cap: None,
+        }
+    }
+
+    /// Bounded queues, writing to a full queue is an error.
+    pub fn with_bounds(input_cap: usize, output_cap: usize) -> Self {
+        Io {
+            input: VecDeque::with_capacity(input_cap),
+            output: VecDeque::with_capacity(output_cap),
+            input_cap: Some(input_cap),
+            output_cap: Some(output_cap),
         }
     }
 
@@ -21,28 +47,164 @@ impl Io {
         self.output
     }
 
+    /// Number of values waiting in the output queue.
+    pub fn output_len(&self) -> usize {
+        self.output.len()
+    }
+
+    /// Front of the output queue, without consuming it.
+    pub fn output_peek(&self) -> Option<i64> {
+        self.output.front().copied()
+    }
+
+    /// Takes every value currently in the output queue.
+    pub fn drain_output(&mut self) -> Vec<i64> {
+        self.output.drain(..).collect()
+    }
+
     /// Read from input
-    pub fn output_read(&mut self) -> Result<i64, Error> {
-        self.output.pop_front().ok_or_else(|| Error::msg("EOF"))
+    pub fn output_read(&mut self) -> Result<i64, IntcodeError> {
+        self.output.pop_front().ok_or(IntcodeError::OutputEmpty)
     }
 
     /// Read from input
-    pub fn input_write(&mut self, value: i64) -> Result<(), Error> {
+    pub fn input_write(&mut self, value: i64) -> Result<(), IntcodeError> {
+        if Some(self.input.len()) == self.input_cap {
+            return Err(IntcodeError::InputFull { value });
+        }
+
         self.input.push_back(value);
 
         Ok(())
     }
+}
 
+impl IntcodeIo for Io {
     /// Read from input
-    pub fn read(&mut self) -> Result<i64, Error> {
+    fn read(&mut self) -> Option<i64> {
         debug!("INPUT: {:?}", &self.input);
-        self.input.pop_front().ok_or(Error::msg("EOF"))
+        self.input.pop_front()
     }
 
     /// Write to output
-    pub fn write(&mut self, value: i64) -> Result<(), Error> {
-        self.output.push_back(value);
+    fn write(&mut self, v: i64) -> Result<(), IntcodeError> {
+        if Some(self.output.len()) == self.output_cap {
+            return Err(IntcodeError::OutputFull { value: v });
+        }
+
+        self.output.push_back(v);
 
         Ok(())
     }
 }
+
+/// A backend connected to channels, for example to chain computers running on different threads.
+/// Reading blocks until a value is sent, or the sending side hangs up.
+#[derive(Debug)]
+pub struct ChannelIo {
+    input: Receiver<i64>,
+    output: Sender<i64>,
+}
+
+impl ChannelIo {
+    pub fn new(input: Receiver<i64>, output: Sender<i64>) -> Self {
+        ChannelIo { input, output }
+    }
+}
+
+impl IntcodeIo for ChannelIo {
+    fn read(&mut self) -> Option<i64> {
+        self.input.recv().ok()
+    }
+
+    fn write(&mut self, v: i64) -> Result<(), IntcodeError> {
+        self.output
+            .send(v)
+            .map_err(|_| IntcodeError::OutputClosed { value: v })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntcodeComputer;
+    use std::sync::mpsc::channel;
+    use std::thread;
+
+    #[test]
+    fn test_bounded_io() {
+        let mut io = Io::with_bounds(1, 2);
+
+        io.input_write(1).unwrap();
+        assert_eq!(io.input_write(2), Err(IntcodeError::InputFull { value: 2 }));
+
+        io.write(1).unwrap();
+        io.write(2).unwrap();
+        assert_eq!(io.write(3), Err(IntcodeError::OutputFull { value: 3 }));
+
+        // Reading frees up room.
+        io.output_read().unwrap();
+        io.write(3).unwrap();
+    }
+
+    #[test]
+    fn test_bounded_io_computer() {
+        let program = IntcodeComputer::parse_program("104,1,104,2,99").unwrap();
+        let mut computer = IntcodeComputer::new(program).with_io(Io::with_bounds(0, 1));
+
+        assert_eq!(
+            computer.run_until_halt(),
+            Err(IntcodeError::OutputFull { value: 2 })
+        );
+        assert_eq!(computer.read_from_output(), Ok(1));
+        assert_eq!(computer.read_from_output(), Err(IntcodeError::OutputEmpty));
+    }
+
+    #[test]
+    fn test_channel_io() {
+        let (input_tx, input_rx) = channel();
+        let (output_tx, output_rx) = channel();
+
+        // Reads a number, doubles it and outputs the result.
+        let program = IntcodeComputer::parse_program("3,9,1002,9,2,9,4,9,99,0").unwrap();
+        let mut computer =
+            IntcodeComputer::new(program).with_io(ChannelIo::new(input_rx, output_tx));
+
+        let handle = thread::spawn(move || computer.run_until_halt());
+
+        input_tx.send(21).unwrap();
+        assert_eq!(output_rx.recv().unwrap(), 42);
+
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_channel_io_hang_up() {
+        let (input_tx, input_rx) = channel::<i64>();
+        let (output_tx, _output_rx) = channel();
+
+        let program = IntcodeComputer::parse_program("3,9,1002,9,2,9,4,9,99,0").unwrap();
+        let mut computer =
+            IntcodeComputer::new(program).with_io(ChannelIo::new(input_rx, output_tx));
+
+        drop(input_tx);
+        assert!(computer.run_until_halt().is_err());
+    }
+
+    #[test]
+    fn test_channel_io_output_closed() {
+        let (input_tx, input_rx) = channel();
+        let (output_tx, output_rx) = channel();
+
+        let program = IntcodeComputer::parse_program("3,9,1002,9,2,9,4,9,99,0").unwrap();
+        let mut computer =
+            IntcodeComputer::new(program).with_io(ChannelIo::new(input_rx, output_tx));
+
+        input_tx.send(21).unwrap();
+        drop(output_rx);
+        assert_eq!(
+            computer.run_until_halt(),
+            Err(IntcodeError::OutputClosed { value: 42 })
+        );
+    }
+}
@@ -1,16 +1,46 @@
-use anyhow::{bail, Context, Error, Result};
+use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 use log::{debug, trace};
 use std::fmt;
+use std::fs;
+use std::path::Path;
 
+mod assembler;
+mod builder;
+mod error;
+#[cfg(test)]
+mod fuzz;
 mod io_wrapper;
+#[cfg(feature = "wasm")]
+mod wasm;
 
-pub use io_wrapper::Io;
-use std::collections::VecDeque;
+pub use builder::Builder;
+pub use error::IntcodeError;
+pub use io_wrapper::{ChannelIo, IntcodeIo, Io};
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
+#[cfg(feature = "wasm")]
+pub use wasm::WasmIntcodeComputer;
 
 pub type Memory = Vec<i64>;
 pub type Address = i64;
 
+/// Returns the program up to (and including) its last nonzero cell.
+/// Useful for comparing a program padded with extra memory against the unpadded original.
+pub fn normalize_program(m: &[i64]) -> &[i64] {
+    let len = m.iter().rposition(|&cell| cell != 0).map_or(0, |i| i + 1);
+    &m[..len]
+}
+
+/// Memory accesses report the current eip, which moves past the instruction while it is decoded,
+/// so errors are pinned back to the address of the instruction.
+fn at_instruction(error: IntcodeError, eip: Address) -> IntcodeError {
+    match error {
+        IntcodeError::OutOfBounds { addr, .. } => IntcodeError::OutOfBounds { addr, eip },
+        error => error,
+    }
+}
+
 #[derive(Debug, PartialOrd, PartialEq)]
 enum BinaryOperation {
     Addition,
@@ -26,12 +56,13 @@ enum BinaryOperation {
 }
 
 impl BinaryOperation {
-    pub fn eval(&self, left: i64, right: i64) -> i64 {
+    /// Returns `None` on overflow.
+    pub fn eval(&self, left: i64, right: i64) -> Option<i64> {
         match self {
-            BinaryOperation::Addition => left + right,
-            BinaryOperation::Multiplication => left * right,
-            BinaryOperation::Equals => (left == right) as i64,
-            BinaryOperation::LessThan => (left < right) as i64,
+            BinaryOperation::Addition => left.checked_add(right),
+            BinaryOperation::Multiplication => left.checked_mul(right),
+            BinaryOperation::Equals => Some((left == right) as i64),
+            BinaryOperation::LessThan => Some((left < right) as i64),
         }
     }
 }
@@ -82,118 +113,535 @@ enum OpCode {
     Halt,
 }
 
+impl OpCode {
+    /// Name of the operation, used for execution statistics.
+    fn name(&self) -> &'static str {
+        match self {
+            OpCode::Binary { t, .. } => match t {
+                BinaryOperation::Addition => "Addition",
+                BinaryOperation::Multiplication => "Multiplication",
+                BinaryOperation::Equals => "Equals",
+                BinaryOperation::LessThan => "LessThan",
+            },
+            OpCode::Input { .. } => "Store",
+            OpCode::Output { .. } => "Output",
+            OpCode::AdjustRelativeBase { .. } => "AdjustRelativeBase",
+            OpCode::Jump { t, .. } => match t {
+                JumpOperation::JumpIfTrue => "JumpIfTrue",
+                JumpOperation::JumpIfFalse => "JumpIfFalse",
+            },
+            OpCode::Halt => "Halt",
+        }
+    }
+}
+
+/// Backing storage for the computer's memory.
+#[derive(Debug)]
+enum MemoryModel {
+    /// A contiguous block of memory starting at address 0.
+    Dense(Memory),
+    /// Every `i64` address (including negative ones) maps to a cell, defaulting to 0.
+    Sparse(HashMap<Address, i64>),
+}
+
 #[derive(Debug)]
-pub struct IntcodeComputer {
-    memory: Memory,
-    pub io: Io,
+pub struct IntcodeComputer<I = Io> {
+    memory: MemoryModel,
+    pub io: I,
     eip: i64,
     ebp: i64,
+    track_self_modification: bool,
+    highest_executed_eip: Option<Address>,
+    self_modifications: Vec<(Address, i64, i64)>,
+    history_capacity: usize,
+    history: VecDeque<HistoryEntry>,
+    last_write: Option<(Address, i64)>,
+    collect_opcode_stats: bool,
+    opcode_stats: HashMap<&'static str, u64>,
+    profiling: bool,
+    execution_profile: HashMap<Address, u64>,
+    instruction_limit: Option<u64>,
+    instructions_executed: u64,
+    /// Whether the program passed `validate` when the computer was created.
+    validated: bool,
 }
 
-#[derive(Debug)]
+/// State needed to undo a single executed instruction.
+#[derive(Debug, Clone, Copy)]
+struct HistoryEntry {
+    eip: Address,
+    ebp: i64,
+    /// Address written by the instruction, along with its previous value.
+    write: Option<(Address, i64)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExecutionStatus {
     NeedInput,
     Halted,
     Done,
 }
 
+/// Summary of a `run_to_completion` call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunResult {
+    /// Either `Halted`, or `NeedInput` if the program paused waiting for input.
+    pub status: ExecutionStatus,
+    /// Number of instructions executed during the run.
+    pub instruction_count: u64,
+    /// All the values that were in the output queue once the run stopped.
+    pub outputs: Vec<i64>,
+}
+
 impl IntcodeComputer {
+    /// Memory size used by `new`.
+    const DEFAULT_MEMORY_CELLS: usize = 1024 * 1024;
+
     pub fn new(mut program: Memory) -> Self {
-        program.resize(1024 * 1024, 0);
-        Self {
-            memory: program,
-            io: Io::new(),
-            eip: 0,
-            ebp: 0,
-        }
+        program.resize(Self::DEFAULT_MEMORY_CELLS, 0);
+        Self::with_memory_model(MemoryModel::Dense(program))
     }
 
     pub fn from_program_without_extra_memory(program: Memory) -> Self {
+        Self::with_memory_model(MemoryModel::Dense(program))
+    }
+
+    /// Creates a computer backed by sparse memory, where any address - including negative ones -
+    /// is valid and defaults to 0.
+    pub fn with_sparse_memory(program: Memory) -> Self {
+        let memory = program
+            .into_iter()
+            .enumerate()
+            .map(|(address, value)| (address as Address, value))
+            .collect();
+
+        Self::with_memory_model(MemoryModel::Sparse(memory))
+    }
+
+    fn with_memory_model(memory: MemoryModel) -> Self {
+        let validated = match &memory {
+            MemoryModel::Dense(memory) => Self::validate(memory).is_ok(),
+            MemoryModel::Sparse(_) => false,
+        };
+
         Self {
-            memory: program,
+            memory,
             io: Io::new(),
             eip: 0,
             ebp: 0,
+            track_self_modification: false,
+            highest_executed_eip: None,
+            self_modifications: vec![],
+            history_capacity: 0,
+            history: VecDeque::new(),
+            last_write: None,
+            collect_opcode_stats: false,
+            opcode_stats: HashMap::new(),
+            profiling: false,
+            execution_profile: HashMap::new(),
+            instruction_limit: None,
+            instructions_executed: 0,
+            validated,
         }
     }
 
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Parses a program whose numbers are separated by commas and/or whitespace.
+    /// `//` and `#` start comments that run to the end of the line.
     pub fn parse_program(input: &str) -> Result<Memory> {
-        input
-            .trim_end()
-            .split(",")
+        let code = input
+            .lines()
+            .map(|line| {
+                let comment = line.find("//").into_iter().chain(line.find('#')).min();
+                &line[..comment.unwrap_or(line.len())]
+            })
+            .join("\n");
+
+        let program = code
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|number| !number.is_empty())
             .map(|number| {
                 number
                     .parse::<i64>()
                     .with_context(|| format!("Expected a number `{}`", number))
             })
-            .collect::<Result<Vec<i64>>>()
-    }
+            .collect::<Result<Vec<i64>>>()?;
 
-    #[inline(always)]
-    pub fn get(&self, i: Address) -> Result<i64> {
-        if i < 0 {
-            bail!("Cannot access memory at a negative offset `0x{:08x}`", i);
+        if program.is_empty() {
+            bail!("Program is empty");
         }
 
-        self.memory
-            .get(i as usize)
-            .with_context(|| {
-                format!(
-                    "Out of bounds access while reading from memory at offset `0x{:08x} ({})`",
-                    i, i
-                )
-            })
-            .map(|i| *i)
+        Ok(program)
     }
 
-    pub fn set_addr(&mut self, i: Address, value: i64) -> Result<()> {
-        if i < 0 {
-            bail!(
-                "Cannot write to memory at a negative offset `0x{:08x} ({})`",
-                i,
-                i
-            );
+    /// Checks the program is well formed, by decoding instructions from address 0 up to the first halt
+    /// (without following jumps), and reporting the first position which isn't a valid instruction.
+    pub fn validate(program: &[i64]) -> Result<()> {
+        let mut address = 0;
+
+        while let Some(&raw) = program.get(address) {
+            let (name, parameters) = assembler::decode(raw)
+                .with_context(|| format!("Invalid instruction `{}` at address {}", raw, address))?;
+
+            if name == "HALT" {
+                return Ok(());
+            }
+
+            if address + parameters.len() >= program.len() {
+                bail!(
+                    "Instruction `{}` at address {} is missing parameters",
+                    raw,
+                    address
+                );
+            }
+
+            address += parameters.len() + 1;
         }
 
-        let stored = self.memory.get_mut(i as usize).with_context(|| {
-            format!(
-                "Out of bounds access while writing to memory at offset `0x{:08x} ({})`",
-                i, i
-            )
-        })?;
+        bail!("Program ends at address {} without halting", address)
+    }
 
-        *stored = value;
-        Ok(())
+    /// Compiles assembly source to a program, see `assembler::assemble` for the syntax.
+    pub fn assemble(src: &str) -> Result<Memory> {
+        assembler::assemble(src)
     }
 
-    pub fn write_to_input(&mut self, value: &[i64]) -> Result<()> {
-        for i in value {
+    pub fn write_to_input(&mut self, value: impl AsRef<[i64]>) -> Result<(), IntcodeError> {
+        for i in value.as_ref() {
             self.io.input_write(*i)?;
         }
 
         Ok(())
     }
 
-    pub fn read_from_output(&mut self) -> Result<i64> {
+    pub fn read_from_output(&mut self) -> Result<i64, IntcodeError> {
         self.io.output_read()
     }
 
+    pub fn output_len(&self) -> usize {
+        self.io.output_len()
+    }
+
+    pub fn peek_output(&self) -> Option<i64> {
+        self.io.output_peek()
+    }
+
     pub fn into_output(self) -> VecDeque<i64> {
         self.io.into_output()
     }
 
-    fn load(&self, parameter: &Parameter) -> Result<i64> {
+    /// Runs until the program halts or needs input, draining the output queue into the result.
+    pub fn run_to_completion(&mut self) -> Result<RunResult, IntcodeError> {
+        let mut instruction_count = 0;
+
+        let status = loop {
+            match self.step()? {
+                ExecutionStatus::Done => instruction_count += 1,
+                status => break status,
+            }
+        };
+
+        let outputs = self.io.drain_output();
+
+        Ok(RunResult {
+            status,
+            instruction_count,
+            outputs,
+        })
+    }
+
+    /// Runs the program, calling `f` for the next value whenever it needs input and the queue is empty.
+    /// Returns `NeedInput` once `f` returns `None`, or `Halted` when the program halts.
+    pub fn run_with_input_fn<F: FnMut() -> Option<i64>>(
+        &mut self,
+        mut f: F,
+    ) -> Result<ExecutionStatus, IntcodeError> {
+        loop {
+            match self.step()? {
+                ExecutionStatus::Done => {}
+                ExecutionStatus::Halted => return Ok(ExecutionStatus::Halted),
+                ExecutionStatus::NeedInput => match f() {
+                    Some(value) => self.io.input_write(value)?,
+                    None => return Ok(ExecutionStatus::NeedInput),
+                },
+            }
+        }
+    }
+
+    /// Loads a memory dump created by `dump_memory`.
+    pub fn load_memory(path: &Path) -> Result<Self> {
+        let dump = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read memory dump `{}`", path.display()))?;
+
+        Ok(Self::new(Self::parse_program(&dump)?))
+    }
+}
+
+impl<I: IntcodeIo> IntcodeComputer<I> {
+    /// Instruction pointer, the address of the next instruction to execute.
+    pub fn eip(&self) -> Address {
+        self.eip
+    }
+
+    /// Relative base, used by relative mode parameters.
+    pub fn ebp(&self) -> i64 {
+        self.ebp
+    }
+
+    /// Decodes the instruction at `address`, returning it along with the address of the next instruction.
+    pub fn disassemble(&self, address: Address) -> Result<(String, Address)> {
+        assembler::disassemble(|i| Ok(self.get(i)?), address)
+    }
+
+    /// Replaces the I/O backend of the computer, keeping its memory and registers.
+    pub fn with_io<J: IntcodeIo>(self, io: J) -> IntcodeComputer<J> {
+        IntcodeComputer {
+            memory: self.memory,
+            io,
+            eip: self.eip,
+            ebp: self.ebp,
+            track_self_modification: self.track_self_modification,
+            highest_executed_eip: self.highest_executed_eip,
+            self_modifications: self.self_modifications,
+            history_capacity: self.history_capacity,
+            history: self.history,
+            last_write: self.last_write,
+            collect_opcode_stats: self.collect_opcode_stats,
+            opcode_stats: self.opcode_stats,
+            profiling: self.profiling,
+            execution_profile: self.execution_profile,
+            instruction_limit: self.instruction_limit,
+            instructions_executed: self.instructions_executed,
+            validated: self.validated,
+        }
+    }
+
+    /// When enabled, counts how many times each address was executed as the start of an instruction.
+    /// Profiling is off by default.
+    pub fn enable_profiling(&mut self, enabled: bool) {
+        self.profiling = enabled;
+    }
+
+    /// Number of times each address was executed as an instruction, see `enable_profiling`.
+    pub fn execution_profile(&self) -> &HashMap<Address, u64> {
+        &self.execution_profile
+    }
+
+    /// When enabled, counts how many times each kind of operation was executed.
+    /// Collection is off by default.
+    pub fn collect_opcode_stats(&mut self, enabled: bool) {
+        self.collect_opcode_stats = enabled;
+    }
+
+    /// Number of executed instructions by operation name (`"Addition"`, `"Store"`, `"Halt"`, ...).
+    pub fn opcode_stats(&self) -> HashMap<&'static str, u64> {
+        self.opcode_stats.clone()
+    }
+
+    /// Records the last `capacity` executed instructions, so they can be undone using `step_back`.
+    /// A capacity of 0 disables recording.
+    pub fn enable_history(&mut self, capacity: usize) {
+        self.history_capacity = capacity;
+        while self.history.len() > capacity {
+            self.history.pop_front();
+        }
+    }
+
+    /// Undoes the last recorded instruction, restoring memory and registers.
+    /// Note that I/O is not rewound.
+    pub fn step_back(&mut self) -> Result<(), IntcodeError> {
+        let entry = self.history.pop_back().ok_or(IntcodeError::NoHistory)?;
+
+        if let Some((address, old_value)) = entry.write {
+            self.write(address, old_value)?;
+        }
+
+        self.eip = entry.eip;
+        self.ebp = entry.ebp;
+
+        Ok(())
+    }
+
+    /// Writes the populated prefix of memory (without trailing zeros) as comma-separated integers.
+    pub fn dump_memory(&self, path: &Path) -> Result<()> {
+        let populated = match &self.memory {
+            MemoryModel::Dense(memory) => normalize_program(memory).to_vec(),
+            MemoryModel::Sparse(memory) => {
+                if memory
+                    .iter()
+                    .any(|(&address, &value)| address < 0 && value != 0)
+                {
+                    bail!("Cannot dump memory containing negative addresses");
+                }
+
+                let mut dense = vec![];
+                for (&address, &value) in memory.iter().filter(|(&a, &v)| a >= 0 && v != 0) {
+                    if dense.len() <= address as usize {
+                        dense.resize(address as usize + 1, 0);
+                    }
+                    dense[address as usize] = value;
+                }
+                dense
+            }
+        };
+
+        fs::write(path, populated.iter().join(","))
+            .with_context(|| format!("Failed to write memory dump `{}`", path.display()))
+    }
+
+    /// When enabled, every write to an address at or below the highest executed instruction
+    /// is recorded, and can be retrieved using `self_modifications`.
+    /// Tracking is off by default.
+    pub fn track_self_modification(&mut self, enabled: bool) {
+        self.track_self_modification = enabled;
+    }
+
+    /// Writes the program made to its own code region, as `(address, old, new)`.
+    pub fn self_modifications(&self) -> &[(Address, i64, i64)] {
+        &self.self_modifications
+    }
+
+    #[inline(always)]
+    pub fn get(&self, i: Address) -> Result<i64, IntcodeError> {
+        let eip = self.eip;
+        let memory = match &self.memory {
+            MemoryModel::Dense(memory) => memory,
+            MemoryModel::Sparse(memory) => return Ok(memory.get(&i).copied().unwrap_or(0)),
+        };
+
+        if i < 0 {
+            return Err(IntcodeError::OutOfBounds { addr: i, eip });
+        }
+
+        memory
+            .get(i as usize)
+            .copied()
+            .ok_or(IntcodeError::OutOfBounds { addr: i, eip })
+    }
+
+    /// Like `get`, with a single bounds check for dense memory.
+    #[inline(always)]
+    fn get_dense(&self, i: Address) -> Result<i64, IntcodeError> {
+        let eip = self.eip;
+        match &self.memory {
+            MemoryModel::Dense(memory) => usize::try_from(i)
+                .ok()
+                .and_then(|index| memory.get(index))
+                .copied()
+                .ok_or(IntcodeError::OutOfBounds { addr: i, eip }),
+            MemoryModel::Sparse(_) => self.get(i),
+        }
+    }
+
+    /// Like `write`, with a single bounds check for dense memory.
+    #[inline(always)]
+    fn set_dense(&mut self, i: Address, value: i64) -> Result<(), IntcodeError> {
+        let eip = self.eip;
+        match &mut self.memory {
+            MemoryModel::Dense(memory) => {
+                let stored = usize::try_from(i)
+                    .ok()
+                    .and_then(|index| memory.get_mut(index))
+                    .ok_or(IntcodeError::OutOfBounds { addr: i, eip })?;
+
+                *stored = value;
+                Ok(())
+            }
+            MemoryModel::Sparse(_) => self.write(i, value),
+        }
+    }
+
+    /// Instructions use the dense accessors once the program is validated,
+    /// unless their writes need to be recorded by `set_addr`.
+    #[inline(always)]
+    fn fast_path(&self) -> bool {
+        self.validated && self.history_capacity == 0 && !self.track_self_modification
+    }
+
+    /// Loads a cell read by an instruction.
+    #[inline(always)]
+    fn fetch(&self, i: Address) -> Result<i64, IntcodeError> {
+        if self.fast_path() {
+            self.get_dense(i)
+        } else {
+            self.get(i)
+        }
+    }
+
+    /// Stores the result of an instruction.
+    #[inline(always)]
+    fn store(&mut self, i: Address, value: i64) -> Result<(), IntcodeError> {
+        if self.fast_path() {
+            self.set_dense(i, value)
+        } else {
+            self.set_addr(i, value)
+        }
+    }
+
+    pub fn set_addr(&mut self, i: Address, value: i64) -> Result<(), IntcodeError> {
+        if self.history_capacity > 0 {
+            self.last_write = self.get(i).ok().map(|old| (i, old));
+        }
+
+        if self.track_self_modification
+            && self
+                .highest_executed_eip
+                .is_some_and(|highest| i <= highest)
+        {
+            let old = self.get(i).ok();
+            self.write(i, value)?;
+
+            if let Some(old) = old {
+                debug!("SELF MODIFICATION: `0x{:08x}` {} => {}", i, old, value);
+                self.self_modifications.push((i, old, value));
+            }
+
+            return Ok(());
+        }
+
+        self.write(i, value)
+    }
+
+    #[inline(always)]
+    fn write(&mut self, i: Address, value: i64) -> Result<(), IntcodeError> {
+        let eip = self.eip;
+        let memory = match &mut self.memory {
+            MemoryModel::Dense(memory) => memory,
+            MemoryModel::Sparse(memory) => {
+                memory.insert(i, value);
+                return Ok(());
+            }
+        };
+
+        if i < 0 {
+            return Err(IntcodeError::OutOfBounds { addr: i, eip });
+        }
+
+        let stored = memory
+            .get_mut(i as usize)
+            .ok_or(IntcodeError::OutOfBounds { addr: i, eip })?;
+
+        *stored = value;
+        Ok(())
+    }
+
+    fn load(&self, parameter: &Parameter) -> Result<i64, IntcodeError> {
         let p = match parameter {
-            Parameter::Position(i) => self.get(*i)?,
+            Parameter::Position(i) => self.fetch(*i)?,
             Parameter::Immediate(i) => *i,
-            Parameter::Relative(i) => self.get(i + self.ebp)?,
+            Parameter::Relative(i) => self.fetch(i.wrapping_add(self.ebp))?,
         };
 
         Ok(p)
     }
 
-    fn read_opcode(&mut self) -> Result<OpCode> {
-        let raw = self.get(self.eip)?;
+    fn read_opcode(&mut self) -> Result<OpCode, IntcodeError> {
+        let eip = self.eip;
+        let raw = self.fetch(eip)?;
+        let invalid = IntcodeError::InvalidOpcode { raw, eip };
 
         // 01001 - first two digits are opcode, rest are parameter modes.
         // ---~~
@@ -209,7 +657,7 @@ impl IntcodeComputer {
                 //          for each ident
                 let args = ($(parse_arguments!(@ $m),)*);
                 if parameters != 0 {
-                    bail!("Did not consume all parameters.");
+                    return Err(invalid);
                 }
                 self.eip += 1;
                 args
@@ -218,7 +666,7 @@ impl IntcodeComputer {
             (@ v) => {{
                 self.eip += 1;
 
-                let parameter_value = self.get(self.eip)?;
+                let parameter_value = self.fetch(self.eip)?;
                 let parameter_mode = parameters % 10;
 
                 let actual_value = match parameter_mode {
@@ -228,7 +676,7 @@ impl IntcodeComputer {
                     1 => Parameter::Immediate(parameter_value),
                     // Relative - use position + relative base.
                     2 => Parameter::Relative(parameter_value),
-                    _ => bail!("Invalid parameter mode `{}`", parameter_mode)
+                    _ => return Err(invalid),
                 };
 
                 parameters /= 10;
@@ -237,7 +685,7 @@ impl IntcodeComputer {
             // Parses an address, addresses are always an i64 (`Address`)
             (@ a) => {{
                 self.eip += 1;
-                let parameter_value = self.get(self.eip)?;
+                let parameter_value = self.fetch(self.eip)?;
                 let parameter_mode = parameters % 10;
 
                 let actual_value = match parameter_mode {
@@ -245,8 +693,8 @@ impl IntcodeComputer {
                     // in this case we use the address value.
                     0 | 1 =>  parameter_value,
                     // Relative - use position + relative base.
-                    2 => parameter_value + self.ebp,
-                    _ => bail!("Invalid parameter mode `{}`", parameter_mode)
+                    2 => parameter_value.wrapping_add(self.ebp),
+                    _ => return Err(invalid),
                 };
 
                 parameters /= 10;
@@ -300,7 +748,7 @@ impl IntcodeComputer {
                 OpCode::AdjustRelativeBase { value }
             }
             99 => OpCode::Halt,
-            _ => bail!("Unknown opcode `{}`", raw),
+            _ => return Err(invalid),
         };
 
         debug!(
@@ -311,10 +759,67 @@ impl IntcodeComputer {
         Ok(op)
     }
 
-    pub fn step(&mut self) -> Result<ExecutionStatus> {
-        let op = self.read_opcode()?;
+    pub fn step(&mut self) -> Result<ExecutionStatus, IntcodeError> {
+        if let Some(limit) = self.instruction_limit {
+            if self.instructions_executed >= limit {
+                return Err(IntcodeError::InstructionLimit { limit });
+            }
+        }
+
+        let eip = self.eip;
+        let ebp = self.ebp;
+
+        if self.track_self_modification {
+            self.highest_executed_eip = self.highest_executed_eip.max(Some(eip));
+        }
+
+        if self.history_capacity > 0 {
+            self.last_write = None;
+        }
+
+        let status = self.execute_instruction()?;
+
+        if status != ExecutionStatus::NeedInput {
+            self.instructions_executed += 1;
+        }
+
+        if self.profiling && status != ExecutionStatus::NeedInput {
+            *self.execution_profile.entry(eip).or_insert(0) += 1;
+        }
+
+        if self.history_capacity > 0 {
+            if let ExecutionStatus::Done = status {
+                if self.history.len() == self.history_capacity {
+                    self.history.pop_front();
+                }
+
+                self.history.push_back(HistoryEntry {
+                    eip,
+                    ebp,
+                    write: self.last_write.take(),
+                });
+            }
+        }
+
+        Ok(status)
+    }
+
+    fn execute_instruction(&mut self) -> Result<ExecutionStatus, IntcodeError> {
+        let eip = self.eip;
+        let op = self.read_opcode().map_err(|e| at_instruction(e, eip))?;
+        let status = self.execute(&op, eip).map_err(|e| at_instruction(e, eip))?;
 
-        match &op {
+        // Instructions starved for input are re-executed once input is available.
+        if self.collect_opcode_stats && status != ExecutionStatus::NeedInput {
+            *self.opcode_stats.entry(op.name()).or_insert(0) += 1;
+        }
+
+        Ok(status)
+    }
+
+    /// Executes `op`, which was decoded from `eip`.
+    fn execute(&mut self, op: &OpCode, eip: Address) -> Result<ExecutionStatus, IntcodeError> {
+        match op {
             OpCode::Binary {
                 left,
                 right,
@@ -323,18 +828,23 @@ impl IntcodeComputer {
             } => {
                 let left = self.load(left)?;
                 let right = self.load(right)?;
-                let result = t.eval(left, right);
-                self.set_addr(*dest, result)?;
+                let result = t.eval(left, right).ok_or(IntcodeError::Overflow {
+                    operation: op.name(),
+                    left,
+                    right,
+                    eip,
+                })?;
+                self.store(*dest, result)?;
             }
             OpCode::Input { address } => match self.io.read() {
-                Err(_) => {
+                None => {
                     self.eip -= 2;
                     debug!("NEED INPUT");
                     return Ok(ExecutionStatus::NeedInput);
                 }
-                Ok(i) => {
+                Some(i) => {
                     trace!("MEMSET: `0x{:08x}`={}", address, i);
-                    self.set_addr(*address, i)?;
+                    self.store(*address, i)?;
                 }
             },
             OpCode::Output { value } => {
@@ -343,7 +853,7 @@ impl IntcodeComputer {
             OpCode::AdjustRelativeBase { value } => {
                 let value = self.load(value)?;
                 trace!("EBP: {} += {}", self.ebp, value);
-                self.ebp += value;
+                self.ebp = self.ebp.wrapping_add(value);
             }
             OpCode::Jump {
                 condition: left,
@@ -372,11 +882,13 @@ impl IntcodeComputer {
         Ok(ExecutionStatus::Done)
     }
 
-    pub fn run_until_halt(&mut self) -> Result<()> {
+    pub fn run_until_halt(&mut self) -> Result<(), IntcodeError> {
         loop {
             let status = self.step()?;
             match status {
-                ExecutionStatus::NeedInput => return Err(Error::msg("EOF")),
+                ExecutionStatus::NeedInput => {
+                    return Err(IntcodeError::NeedInput { eip: self.eip })
+                }
                 ExecutionStatus::Halted => return Ok(()),
                 ExecutionStatus::Done => {}
             }
@@ -384,9 +896,16 @@ impl IntcodeComputer {
     }
 }
 
-impl fmt::Display for IntcodeComputer {
+impl<I> fmt::Display for IntcodeComputer<I> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let repr = self.memory.iter().join(",");
+        let repr = match &self.memory {
+            MemoryModel::Dense(memory) => memory.iter().join(","),
+            MemoryModel::Sparse(memory) => memory
+                .iter()
+                .sorted_by_key(|(address, _)| **address)
+                .map(|(address, value)| format!("{}:{}", address, value))
+                .join(","),
+        };
         f.write_str(&repr)?;
 
         Ok(())
@@ -422,6 +941,448 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_parse_program_with_comments() {
+        let program = "\
+            // Adds the two numbers at the end of the program.
+            1, 9, 10, 11,   # add
+            4, 11,          # output
+            99,
+            // operands
+            20,\t22,
+            0,
+        ";
+
+        assert_eq!(
+            IntcodeComputer::parse_program(program).unwrap(),
+            vec![1, 9, 10, 11, 4, 11, 99, 20, 22, 0]
+        );
+        assert_eq!(
+            IntcodeComputer::parse_program("1,0,0,0,99\n").unwrap(),
+            vec![1, 0, 0, 0, 99]
+        );
+
+        let err = IntcodeComputer::parse_program("1, 0, x, 99 // bad").unwrap_err();
+        assert_eq!(err.to_string(), "Expected a number `x`");
+        assert!(IntcodeComputer::parse_program("// nothing here").is_err());
+    }
+
+    #[test]
+    fn test_parse_program_separators() {
+        let expected = vec![1, 0, 0, 0, 99];
+
+        for program in &[
+            "1 0 0 0 99",
+            "1,0,0,0,99",
+            "1\n0\n0\n0\n99\n",
+            "1, 0,,0\t0 99",
+        ] {
+            assert_eq!(IntcodeComputer::parse_program(program).unwrap(), expected);
+        }
+
+        let computer = IntcodeComputer::from_program_without_extra_memory(
+            IntcodeComputer::parse_program("1 0 0 0 99").unwrap(),
+        );
+        assert_eq!(computer.to_string(), "1,0,0,0,99");
+    }
+
+    #[test]
+    fn test_validate() {
+        let validate = |program: &str| {
+            IntcodeComputer::validate(&IntcodeComputer::parse_program(program).unwrap())
+                .map_err(|e| e.to_string())
+        };
+
+        assert_eq!(validate("1,0,0,0,99"), Ok(()));
+        // Only instructions up to the first halt are checked, the rest may be data.
+        assert_eq!(validate("1101,1,2,0,1105,1,9,99,-5,99"), Ok(()));
+
+        assert_eq!(
+            validate("1,0,0,0,2,0,0,0"),
+            Err("Program ends at address 8 without halting".to_owned())
+        );
+        assert_eq!(
+            validate("1,0,0,0,2,0"),
+            Err("Instruction `2` at address 4 is missing parameters".to_owned())
+        );
+        assert_eq!(
+            validate("1,0,0,0,42,99"),
+            Err("Invalid instruction `42` at address 4".to_owned())
+        );
+        assert_eq!(validate("1101,1,2,0,199"), Ok(()));
+        assert_eq!(
+            validate("304,0,99"),
+            Err("Invalid instruction `304` at address 0".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let program =
+            IntcodeComputer::parse_program("1,0,0,0,1102,9223372036854775807,2,0,99").unwrap();
+        let mut computer = IntcodeComputer::new(program);
+
+        let err = computer.run_until_halt().unwrap_err();
+        assert_eq!(
+            err,
+            IntcodeError::Overflow {
+                operation: "Multiplication",
+                left: 9223372036854775807,
+                right: 2,
+                eip: 4
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Multiplication of `9223372036854775807` and `2` overflows at eip 4"
+        );
+
+        let program = IntcodeComputer::parse_program("1101,-9223372036854775808,-1,0,99").unwrap();
+        assert!(matches!(
+            IntcodeComputer::new(program).run_until_halt(),
+            Err(IntcodeError::Overflow { eip: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_normalize_program() {
+        let program = IntcodeComputer::parse_program("1,0,0,0,99").unwrap();
+        let mut padded = program.clone();
+        padded.resize(64, 0);
+
+        assert_eq!(normalize_program(&padded), normalize_program(&program));
+        assert_eq!(normalize_program(&padded), &[1, 0, 0, 0, 99][..]);
+        assert_eq!(normalize_program(&[0, 0]), &[] as &[i64]);
+    }
+
+    #[test]
+    fn test_sparse_memory_negative_address() {
+        let mut computer = IntcodeComputer::with_sparse_memory(vec![99]);
+        computer.set_addr(-3, 42).unwrap();
+
+        assert_eq!(computer.get(-3).unwrap(), 42);
+        assert_eq!(computer.get(-4).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_dense_memory_negative_address() {
+        let mut computer = IntcodeComputer::new(vec![99]);
+        assert_eq!(
+            computer.set_addr(-3, 42),
+            Err(IntcodeError::OutOfBounds { addr: -3, eip: 0 })
+        );
+        assert_eq!(
+            computer.get(-3),
+            Err(IntcodeError::OutOfBounds { addr: -3, eip: 0 })
+        );
+    }
+
+    #[test]
+    fn test_dense_accessors() {
+        let mut computer = IntcodeComputer::from_program_without_extra_memory(vec![99, 0]);
+        computer.set_dense(1, 42).unwrap();
+
+        assert_eq!(computer.get_dense(1), Ok(42));
+        assert_eq!(
+            computer.get_dense(-1),
+            Err(IntcodeError::OutOfBounds { addr: -1, eip: 0 })
+        );
+        // Would alias address 1 if truncated to a 32 bit `usize`.
+        assert_eq!(
+            computer.get_dense((1 << 32) + 1),
+            Err(IntcodeError::OutOfBounds {
+                addr: (1 << 32) + 1,
+                eip: 0
+            })
+        );
+        assert_eq!(
+            computer.set_dense(2, 1),
+            Err(IntcodeError::OutOfBounds { addr: 2, eip: 0 })
+        );
+    }
+
+    #[test]
+    fn test_fast_path_matches_set_addr() {
+        // Quine using relative mode, plus a program with a large multiplication.
+        let programs = [
+            "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99",
+            "3,3,1105,-1,9,1101,0,0,12,4,12,99,1",
+            "1102,34915192,34915192,7,4,7,99,0",
+        ];
+
+        for program in programs.iter() {
+            let bytecode = IntcodeComputer::parse_program(program).unwrap();
+
+            let mut fast = IntcodeComputer::new(bytecode.clone());
+            // History forces every write through `set_addr`.
+            let mut slow = IntcodeComputer::new(bytecode);
+            slow.enable_history(1);
+            assert!(fast.fast_path() && !slow.fast_path());
+
+            for computer in [&mut fast, &mut slow].iter_mut() {
+                computer.write_to_input([0]).unwrap();
+                computer.run_until_halt().unwrap();
+            }
+
+            assert_eq!(fast.to_string(), slow.to_string());
+            assert_eq!(fast.into_output(), slow.into_output());
+        }
+
+        // Programs which fail validation, and sparse memory, stay on the checked accessors.
+        assert!(!IntcodeComputer::new(vec![1, 0, 0]).fast_path());
+        assert!(!IntcodeComputer::with_sparse_memory(vec![99]).fast_path());
+    }
+
+    #[test]
+    fn test_halt_ignores_modes() {
+        for &halt in &[199, 10099] {
+            let mut computer = IntcodeComputer::new(vec![104, 7, halt, 104, 8, 99]);
+            computer.run_until_halt().unwrap();
+
+            assert_eq!(computer.into_output(), vec![7]);
+        }
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        // Outputs the value at address 10, past the end of memory.
+        let bytecode = IntcodeComputer::parse_program("104,1,4,10,99").unwrap();
+        let mut computer = IntcodeComputer::from_program_without_extra_memory(bytecode);
+
+        // Reported at the instruction, not at the parameter eip had moved on to.
+        let error = computer.run_until_halt().unwrap_err();
+        assert_eq!(error, IntcodeError::OutOfBounds { addr: 10, eip: 2 });
+        assert!(error.to_string().ends_with("at eip 2"), "{}", error);
+
+        // Writes are reported the same way.
+        let mut computer =
+            IntcodeComputer::from_program_without_extra_memory(vec![1101, 1, 1, 9, 99]);
+        assert_eq!(
+            computer.run_until_halt(),
+            Err(IntcodeError::OutOfBounds { addr: 9, eip: 0 })
+        );
+    }
+
+    #[test]
+    fn test_decode_error_contains_eip() {
+        let bytecode = IntcodeComputer::parse_program("1101,1,1,0,77,99").unwrap();
+        let mut computer = IntcodeComputer::new(bytecode);
+
+        let err = computer.run_until_halt().unwrap_err();
+        assert_eq!(err, IntcodeError::InvalidOpcode { raw: 77, eip: 4 });
+        assert_eq!(err.to_string(), "Invalid instruction `77` at eip 4");
+
+        // Invalid parameter modes.
+        for program in &["301,0,99", "11104,0,99"] {
+            let bytecode = IntcodeComputer::parse_program(program).unwrap();
+            assert!(matches!(
+                IntcodeComputer::new(bytecode).run_until_halt(),
+                Err(IntcodeError::InvalidOpcode { eip: 0, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_track_self_modification() {
+        let bytecode = IntcodeComputer::parse_program("1101,1,1,0,1101,2,2,9,99,0").unwrap();
+
+        let mut computer = IntcodeComputer::new(bytecode.clone());
+        computer.run_until_halt().unwrap();
+        assert!(computer.self_modifications().is_empty());
+
+        let mut computer = IntcodeComputer::new(bytecode);
+        computer.track_self_modification(true);
+        computer.run_until_halt().unwrap();
+        assert_eq!(computer.self_modifications(), &[(0, 1101, 2)]);
+    }
+
+    #[test]
+    fn test_dump_and_load_memory() {
+        let bytecode = IntcodeComputer::parse_program("3,9,1002,9,2,9,4,9,99,0").unwrap();
+        let mut computer = IntcodeComputer::new(bytecode);
+        computer.write_to_input([21]).unwrap();
+        // Store the input, and double it.
+        computer.step().unwrap();
+        computer.step().unwrap();
+
+        let path = std::env::temp_dir().join(format!("intcode-dump-{}", std::process::id()));
+        computer.dump_memory(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "3,9,1002,9,2,9,4,9,99,42"
+        );
+
+        let loaded = IntcodeComputer::load_memory(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.to_string(), computer.to_string());
+    }
+
+    #[test]
+    fn test_peek_output() {
+        let bytecode = IntcodeComputer::parse_program("104,7,104,8,99").unwrap();
+        let mut computer = IntcodeComputer::new(bytecode);
+
+        assert_eq!(computer.output_len(), 0);
+        assert_eq!(computer.peek_output(), None);
+
+        computer.run_until_halt().unwrap();
+
+        assert_eq!(computer.output_len(), 2);
+        assert_eq!(computer.peek_output(), Some(7));
+        // Peeking does not consume the value.
+        assert_eq!(computer.read_from_output().unwrap(), 7);
+        assert_eq!(computer.output_len(), 1);
+        assert_eq!(computer.peek_output(), Some(8));
+    }
+
+    #[test]
+    fn test_run_until_halt_need_input() {
+        let bytecode = IntcodeComputer::parse_program("1101,1,1,0,3,0,99").unwrap();
+        let mut computer = IntcodeComputer::new(bytecode);
+
+        let err = computer.run_until_halt().unwrap_err();
+        assert_eq!(err, IntcodeError::NeedInput { eip: 4 });
+        assert!(err.to_string().contains("at eip 4"));
+
+        // Genuine failures are not reported as missing input.
+        let bytecode = IntcodeComputer::parse_program("77,99").unwrap();
+        let err = IntcodeComputer::new(bytecode).run_until_halt().unwrap_err();
+        assert!(!matches!(err, IntcodeError::NeedInput { .. }));
+    }
+
+    #[test]
+    fn test_step_back() {
+        let bytecode =
+            IntcodeComputer::parse_program("1101,1,1,15,1102,3,3,16,109,5,1101,5,5,17,99,0,0,0")
+                .unwrap();
+        let mut computer = IntcodeComputer::from_program_without_extra_memory(bytecode);
+        computer.enable_history(8);
+
+        computer.step().unwrap();
+        let (memory, eip, ebp) = (computer.to_string(), computer.eip, computer.ebp);
+
+        computer.step().unwrap();
+        computer.step().unwrap();
+        assert_eq!(computer.ebp, 5);
+        assert_ne!(computer.to_string(), memory);
+
+        computer.step_back().unwrap();
+        computer.step_back().unwrap();
+
+        assert_eq!(computer.to_string(), memory);
+        assert_eq!((computer.eip, computer.ebp), (eip, ebp));
+    }
+
+    #[test]
+    fn test_step_back_without_history() {
+        let bytecode = IntcodeComputer::parse_program("1101,1,1,5,99,0").unwrap();
+        let mut computer = IntcodeComputer::new(bytecode);
+        computer.step().unwrap();
+
+        assert_eq!(computer.step_back(), Err(IntcodeError::NoHistory));
+    }
+
+    #[test]
+    fn test_run_to_completion() {
+        let bytecode = IntcodeComputer::parse_program("1101,2,3,9,4,9,104,7,99,0").unwrap();
+        let mut computer = IntcodeComputer::new(bytecode);
+
+        assert_eq!(
+            computer.run_to_completion().unwrap(),
+            RunResult {
+                status: ExecutionStatus::Halted,
+                instruction_count: 3,
+                outputs: vec![5, 7],
+            }
+        );
+        assert_eq!(computer.output_len(), 0);
+
+        let bytecode = IntcodeComputer::parse_program("104,1,3,0,99").unwrap();
+        let result = IntcodeComputer::new(bytecode).run_to_completion().unwrap();
+        assert_eq!(result.status, ExecutionStatus::NeedInput);
+        assert_eq!(result.instruction_count, 1);
+        assert_eq!(result.outputs, vec![1]);
+    }
+
+    #[test]
+    fn test_run_with_input_fn() {
+        // Echoes its input forever.
+        let bytecode = IntcodeComputer::parse_program("3,100,4,100,1105,1,0").unwrap();
+        let mut computer = IntcodeComputer::new(bytecode);
+
+        let mut counter = 0;
+        let status = computer
+            .run_with_input_fn(|| {
+                counter += 1;
+                if counter <= 3 {
+                    Some(counter)
+                } else {
+                    None
+                }
+            })
+            .unwrap();
+
+        assert_eq!(status, ExecutionStatus::NeedInput);
+        assert_eq!(computer.into_output(), vec![1, 2, 3]);
+
+        // Queued input is used before calling the closure.
+        let bytecode = IntcodeComputer::parse_program("3,0,3,1,4,0,4,1,99").unwrap();
+        let mut computer = IntcodeComputer::new(bytecode);
+        computer.write_to_input([10]).unwrap();
+
+        let status = computer.run_with_input_fn(|| Some(20)).unwrap();
+        assert_eq!(status, ExecutionStatus::Halted);
+        assert_eq!(computer.into_output(), vec![10, 20]);
+    }
+
+    #[test]
+    fn test_opcode_stats() {
+        let bytecode =
+            IntcodeComputer::parse_program("3,13,1002,13,3,13,4,13,1105,0,0,99,0,0").unwrap();
+        let mut computer = IntcodeComputer::new(bytecode);
+        computer.collect_opcode_stats(true);
+
+        // Starved for input first, this should not be counted.
+        computer.step().unwrap();
+        computer.write_to_input([4]).unwrap();
+        computer.run_until_halt().unwrap();
+
+        let stats = computer.opcode_stats();
+        assert_eq!(stats.get("Store"), Some(&1));
+        assert_eq!(stats.get("Multiplication"), Some(&1));
+        assert_eq!(stats.get("Output"), Some(&1));
+        assert_eq!(stats.get("JumpIfTrue"), Some(&1));
+        assert_eq!(stats.get("Halt"), Some(&1));
+        assert_eq!(stats.get("Addition"), None);
+    }
+
+    #[test]
+    fn test_opcode_stats_disabled() {
+        let bytecode = IntcodeComputer::parse_program("1101,1,1,0,99").unwrap();
+        let mut computer = IntcodeComputer::new(bytecode);
+        computer.run_until_halt().unwrap();
+
+        assert!(computer.opcode_stats().is_empty());
+    }
+
+    #[test]
+    fn test_execution_profile() {
+        // Counts down from 3, the loop body starts at address 4.
+        let bytecode =
+            IntcodeComputer::parse_program("1101,3,0,12,1001,12,-1,12,1005,12,4,99,0").unwrap();
+        let mut computer = IntcodeComputer::new(bytecode);
+        computer.enable_profiling(true);
+        computer.run_until_halt().unwrap();
+
+        let profile = computer.execution_profile();
+        assert_eq!(profile.get(&0), Some(&1));
+        assert_eq!(profile.get(&4), Some(&3));
+        assert_eq!(profile.get(&8), Some(&3));
+        assert_eq!(profile.get(&11), Some(&1));
+        assert_eq!(profile.len(), 4);
+    }
+
     #[test]
     fn test_no_input() {
         test_memory!("1,0,0,0,99", vec![], "2,0,0,0,99");
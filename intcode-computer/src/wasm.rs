@@ -0,0 +1,59 @@
+use crate::{IntcodeComputer, IntcodeError};
+use wasm_bindgen::prelude::*;
+
+fn to_js(e: IntcodeError) -> JsValue {
+    JsValue::from_str(&e.to_string())
+}
+
+/// `IntcodeComputer` exported to JavaScript, values cross as `BigInt`s.
+#[wasm_bindgen(js_name = IntcodeComputer)]
+pub struct WasmIntcodeComputer {
+    computer: IntcodeComputer,
+}
+
+#[wasm_bindgen(js_class = IntcodeComputer)]
+impl WasmIntcodeComputer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(program: Vec<i64>) -> WasmIntcodeComputer {
+        WasmIntcodeComputer {
+            computer: IntcodeComputer::new(program),
+        }
+    }
+
+    #[wasm_bindgen(js_name = writeToInput)]
+    pub fn write_to_input(&mut self, values: Vec<i64>) -> Result<(), JsValue> {
+        self.computer.write_to_input(&values).map_err(to_js)
+    }
+
+    /// Executes a single instruction, returns `"NeedInput"`, `"Halted"` or `"Done"`.
+    pub fn step(&mut self) -> Result<String, JsValue> {
+        let status = self.computer.step().map_err(to_js)?;
+        Ok(format!("{:?}", status))
+    }
+
+    #[wasm_bindgen(js_name = readFromOutput)]
+    pub fn read_from_output(&mut self) -> Result<i64, JsValue> {
+        self.computer.read_from_output().map_err(to_js)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExecutionStatus;
+
+    #[test]
+    fn test_wasm_computer() {
+        // Outputs the input times 3.
+        let mut computer = WasmIntcodeComputer::new(vec![3, 9, 1002, 9, 3, 9, 4, 9, 99, 0]);
+        computer.write_to_input(vec![14]).unwrap();
+
+        let mut status = computer.step().unwrap();
+        while status == "Done" {
+            status = computer.step().unwrap();
+        }
+
+        assert_eq!(status, format!("{:?}", ExecutionStatus::Halted));
+        assert_eq!(computer.read_from_output().unwrap(), 42);
+    }
+}